@@ -0,0 +1,286 @@
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::bitboard::{piece_index, squares, BitMove, Position, PIECE_TYPES};
+use crate::pieces::{PieceType, Side};
+
+// indexed by piece_index, the king is never traded so it counts nothing
+const VALUES: [i32; 6] = [100, 320, 330, 500, 900, 0];
+
+// placement bonuses from White's side, rank 8 first like the squares themselves
+#[rustfmt::skip]
+const PIECE_SQUARE: [[i32; 64]; 6] = [
+    // pawn
+    [
+         0,  0,  0,  0,  0,  0,  0,  0,
+        50, 50, 50, 50, 50, 50, 50, 50,
+        10, 10, 20, 30, 30, 20, 10, 10,
+         5,  5, 10, 25, 25, 10,  5,  5,
+         0,  0,  0, 20, 20,  0,  0,  0,
+         5, -5,-10,  0,  0,-10, -5,  5,
+         5, 10, 10,-20,-20, 10, 10,  5,
+         0,  0,  0,  0,  0,  0,  0,  0,
+    ],
+    // knight
+    [
+        -50,-40,-30,-30,-30,-30,-40,-50,
+        -40,-20,  0,  0,  0,  0,-20,-40,
+        -30,  0, 10, 15, 15, 10,  0,-30,
+        -30,  5, 15, 20, 20, 15,  5,-30,
+        -30,  0, 15, 20, 20, 15,  0,-30,
+        -30,  5, 10, 15, 15, 10,  5,-30,
+        -40,-20,  0,  5,  5,  0,-20,-40,
+        -50,-40,-30,-30,-30,-30,-40,-50,
+    ],
+    // bishop
+    [
+        -20,-10,-10,-10,-10,-10,-10,-20,
+        -10,  0,  0,  0,  0,  0,  0,-10,
+        -10,  0,  5, 10, 10,  5,  0,-10,
+        -10,  5,  5, 10, 10,  5,  5,-10,
+        -10,  0, 10, 10, 10, 10,  0,-10,
+        -10, 10, 10, 10, 10, 10, 10,-10,
+        -10,  5,  0,  0,  0,  0,  5,-10,
+        -20,-10,-10,-10,-10,-10,-10,-20,
+    ],
+    // rook
+    [
+         0,  0,  0,  0,  0,  0,  0,  0,
+         5, 10, 10, 10, 10, 10, 10,  5,
+        -5,  0,  0,  0,  0,  0,  0, -5,
+        -5,  0,  0,  0,  0,  0,  0, -5,
+        -5,  0,  0,  0,  0,  0,  0, -5,
+        -5,  0,  0,  0,  0,  0,  0, -5,
+        -5,  0,  0,  0,  0,  0,  0, -5,
+         0,  0,  0,  5,  5,  0,  0,  0,
+    ],
+    // queen
+    [
+        -20,-10,-10, -5, -5,-10,-10,-20,
+        -10,  0,  0,  0,  0,  0,  0,-10,
+        -10,  0,  5,  5,  5,  5,  0,-10,
+         -5,  0,  5,  5,  5,  5,  0, -5,
+          0,  0,  5,  5,  5,  5,  0, -5,
+        -10,  5,  5,  5,  5,  5,  0,-10,
+        -10,  0,  5,  0,  0,  0,  0,-10,
+        -20,-10,-10, -5, -5,-10,-10,-20,
+    ],
+    // king
+    [
+        -30,-40,-40,-50,-50,-40,-40,-30,
+        -30,-40,-40,-50,-50,-40,-40,-30,
+        -30,-40,-40,-50,-50,-40,-40,-30,
+        -30,-40,-40,-50,-50,-40,-40,-30,
+        -20,-30,-30,-40,-40,-30,-30,-20,
+        -10,-20,-20,-20,-20,-20,-20,-10,
+         20, 20,  0,  0,  0,  0, 20, 20,
+         20, 30, 10,  0,  0, 10, 30, 20,
+    ],
+];
+
+pub const MATE: i32 = 100_000;
+const INFINITY: i32 = 1_000_000;
+pub const MAX_DEPTH: u32 = 64;
+
+// material and placement, from the side to move's point of view
+pub fn evaluate(position: &Position) -> i32 {
+    let mut score = 0;
+    for piece_type in PIECE_TYPES {
+        let idx = piece_index(piece_type);
+        for square in squares(position.bitboard(Side::White, piece_type)) {
+            score += VALUES[idx] + PIECE_SQUARE[idx][square];
+        }
+        // Black reads the tables upside down
+        for square in squares(position.bitboard(Side::Black, piece_type)) {
+            score -= VALUES[idx] + PIECE_SQUARE[idx][square ^ 56];
+        }
+    }
+    match position.turn {
+        Side::White => score,
+        Side::Black => -score,
+    }
+}
+
+fn captured(position: &Position, played: &BitMove) -> Option<PieceType> {
+    if let Some((_, piece_type)) = position.piece_at(played.to as usize) {
+        return Some(piece_type);
+    }
+    let (_, mover) = position.piece_at(played.from as usize)?;
+    ((mover == PieceType::Pawn) && (Some(played.to as usize) == position.en_passant))
+        .then_some(PieceType::Pawn)
+}
+
+// captures first, most valuable victim by least valuable attacker, then promotions
+fn order(position: &Position, moves: &mut [BitMove]) {
+    moves.sort_by_key(|played| {
+        let mut key = 0;
+        if let Some(victim) = captured(position, played) {
+            let attacker = position.piece_at(played.from as usize).unwrap().1;
+            key += 10 * VALUES[piece_index(victim)] - VALUES[piece_index(attacker)] + 10_000;
+        }
+        if let Some(piece_type) = played.promotion {
+            key += VALUES[piece_index(piece_type)];
+        }
+        Reverse(key)
+    });
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits {
+    pub depth: Option<u32>,
+    pub movetime: Option<Duration>,
+}
+
+// outcome of the deepest iteration that finished
+#[derive(Debug, Clone, Default)]
+pub struct SearchInfo {
+    pub depth: u32,
+    pub score: i32,
+    pub nodes: u64,
+    pub best: Option<BitMove>,
+}
+
+struct Search<'a> {
+    stop: &'a AtomicBool,
+    deadline: Option<Instant>,
+    nodes: u64,
+    aborted: bool,
+}
+
+impl Search<'_> {
+    fn out_of_time(&mut self) -> bool {
+        if !self.aborted && self.nodes.is_multiple_of(1024) {
+            self.aborted = self.stop.load(Ordering::Relaxed)
+                || self
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        self.aborted
+    }
+
+    fn negamax(
+        &mut self,
+        position: &Position,
+        depth: u32,
+        mut alpha: i32,
+        beta: i32,
+        ply: i32,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.out_of_time() {
+            return 0;
+        }
+        if depth == 0 {
+            return self.quiescence(position, alpha, beta);
+        }
+        if position.halfmove_clock >= 100 {
+            return 0;
+        }
+        let mut moves = position.legal_moves();
+        if moves.is_empty() {
+            return if position.in_check() { ply - MATE } else { 0 };
+        }
+        order(position, &mut moves);
+        for played in &moves {
+            let score = -self.negamax(&position.make(played), depth - 1, -beta, -alpha, ply + 1);
+            if self.aborted {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+
+    // follows captures to the end so a hanging piece is not missed at the horizon
+    fn quiescence(&mut self, position: &Position, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.out_of_time() {
+            return 0;
+        }
+        let stand_pat = evaluate(position);
+        if stand_pat >= beta {
+            return beta;
+        }
+        alpha = alpha.max(stand_pat);
+        let mut moves: Vec<BitMove> = position
+            .legal_moves()
+            .into_iter()
+            .filter(|played| {
+                captured(position, played).is_some() || (played.promotion == Some(PieceType::Queen))
+            })
+            .collect();
+        order(position, &mut moves);
+        for played in &moves {
+            let score = -self.quiescence(&position.make(played), -beta, -alpha);
+            if self.aborted {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+}
+
+// iterative deepening until the depth, the move time or the stop flag runs out;
+// `report` hears about every iteration that finishes
+pub fn search(
+    position: &Position,
+    limits: &Limits,
+    stop: &AtomicBool,
+    mut report: impl FnMut(&SearchInfo),
+) -> SearchInfo {
+    let mut search = Search {
+        stop,
+        deadline: limits.movetime.map(|movetime| Instant::now() + movetime),
+        nodes: 0,
+        aborted: false,
+    };
+    let mut moves = position.legal_moves();
+    order(position, &mut moves);
+    // something to play even if the first iteration does not finish
+    let mut info = SearchInfo {
+        best: moves.first().copied(),
+        ..Default::default()
+    };
+    if moves.is_empty() {
+        return info;
+    }
+    for depth in 1..=limits.depth.unwrap_or(MAX_DEPTH).min(MAX_DEPTH) {
+        let mut alpha = -INFINITY;
+        let mut best = 0;
+        for (i, played) in moves.iter().enumerate() {
+            let score = -search.negamax(&position.make(played), depth - 1, -INFINITY, -alpha, 1);
+            if search.aborted {
+                break;
+            }
+            if score > alpha {
+                alpha = score;
+                best = i;
+            }
+        }
+        if search.aborted {
+            break;
+        }
+        info = SearchInfo {
+            depth,
+            score: alpha,
+            nodes: search.nodes,
+            best: Some(moves[best]),
+        };
+        report(&info);
+        // the best move so far is searched first next time
+        moves[..=best].rotate_right(1);
+        if alpha.abs() >= MATE - MAX_DEPTH as i32 {
+            break;
+        }
+    }
+    info.nodes = search.nodes;
+    info
+}
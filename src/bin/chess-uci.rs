@@ -0,0 +1,4 @@
+// the computer player as a UCI engine for other chess GUIs, without a window of its own
+fn main() {
+    chess::uci::run(std::io::stdin().lock(), std::io::stdout());
+}
@@ -0,0 +1,528 @@
+use crate::grid::CellId;
+use crate::pieces::{PieceType, Side};
+use crate::zobrist;
+
+// one bit per square, numbered like CellId::to_vec_idx: a8 is bit 0, h1 is bit 63
+pub type Bitboard = u64;
+
+pub fn bit(square: usize) -> Bitboard {
+    1 << square
+}
+
+// squares of the set bits, lowest first
+pub fn squares(mut bitboard: Bitboard) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+        let square = bitboard.trailing_zeros() as usize;
+        bitboard &= bitboard - 1;
+        Some(square)
+    })
+}
+
+const RANK_8: Bitboard = 0xff;
+// a8 is a light square
+const LIGHT_SQUARES: Bitboard = 0x55aa_55aa_55aa_55aa;
+const RANK_7: Bitboard = RANK_8 << 8;
+const RANK_2: Bitboard = RANK_8 << 48;
+const RANK_1: Bitboard = RANK_8 << 56;
+
+// (file, row) steps, rows counted downwards from rank 8 like CellId
+const KNIGHT_STEPS: [(i32, i32); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+const KING_STEPS: [(i32, i32); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+// rook directions first, then the bishop ones
+const RAY_STEPS: [(i32, i32); 8] = [
+    (1, 0),
+    (0, 1),
+    (-1, 0),
+    (0, -1),
+    (1, 1),
+    (-1, 1),
+    (-1, -1),
+    (1, -1),
+];
+
+const fn on_board(x: i32, y: i32) -> bool {
+    (x >= 0) & (x < 8) & (y >= 0) & (y < 8)
+}
+
+const fn step_table(steps: &[(i32, i32)]) -> [Bitboard; 64] {
+    let mut table = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        let (x, y) = ((square % 8) as i32, (square / 8) as i32);
+        let mut i = 0;
+        while i < steps.len() {
+            let (x_, y_) = (x + steps[i].0, y + steps[i].1);
+            if on_board(x_, y_) {
+                table[square] |= 1 << (x_ + y_ * 8);
+            }
+            i += 1;
+        }
+        square += 1;
+    }
+    table
+}
+
+const fn ray_table() -> [[Bitboard; 64]; 8] {
+    let mut table = [[0; 64]; 8];
+    let mut dir = 0;
+    while dir < 8 {
+        let mut square = 0;
+        while square < 64 {
+            let (mut x, mut y) = ((square % 8) as i32, (square / 8) as i32);
+            loop {
+                x += RAY_STEPS[dir].0;
+                y += RAY_STEPS[dir].1;
+                if !on_board(x, y) {
+                    break;
+                }
+                table[dir][square] |= 1 << (x + y * 8);
+            }
+            square += 1;
+        }
+        dir += 1;
+    }
+    table
+}
+
+pub const KNIGHT_ATTACKS: [Bitboard; 64] = step_table(&KNIGHT_STEPS);
+pub const KING_ATTACKS: [Bitboard; 64] = step_table(&KING_STEPS);
+// squares a pawn of each side takes on, white first
+pub const PAWN_ATTACKS: [[Bitboard; 64]; 2] = [
+    step_table(&[(-1, -1), (1, -1)]),
+    step_table(&[(-1, 1), (1, 1)]),
+];
+const RAYS: [[Bitboard; 64]; 8] = ray_table();
+
+// walks each ray up to and including the first occupied square
+fn slide(square: usize, occupied: Bitboard, dirs: std::ops::Range<usize>) -> Bitboard {
+    let mut attacks = 0;
+    for dir in dirs {
+        let ray = RAYS[dir][square];
+        let blockers = ray & occupied;
+        if blockers == 0 {
+            attacks |= ray;
+            continue;
+        }
+        let (x, y) = RAY_STEPS[dir];
+        let blocker = if x + y * 8 > 0 {
+            blockers.trailing_zeros()
+        } else {
+            63 - blockers.leading_zeros()
+        };
+        attacks |= ray ^ RAYS[dir][blocker as usize];
+    }
+    attacks
+}
+
+pub fn rook_attacks(square: usize, occupied: Bitboard) -> Bitboard {
+    slide(square, occupied, 0..4)
+}
+
+pub fn bishop_attacks(square: usize, occupied: Bitboard) -> Bitboard {
+    slide(square, occupied, 4..8)
+}
+
+pub fn side_index(side: Side) -> usize {
+    match side {
+        Side::White => 0,
+        Side::Black => 1,
+    }
+}
+
+pub const PIECE_TYPES: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+
+pub fn piece_index(piece_type: PieceType) -> usize {
+    match piece_type {
+        PieceType::Pawn => 0,
+        PieceType::Knight => 1,
+        PieceType::Bishop => 2,
+        PieceType::Rook => 3,
+        PieceType::Queen => 4,
+        PieceType::King => 5,
+    }
+}
+
+pub const WHITE_KINGSIDE: u8 = 1;
+pub const WHITE_QUEENSIDE: u8 = 2;
+pub const BLACK_KINGSIDE: u8 = 4;
+pub const BLACK_QUEENSIDE: u8 = 8;
+
+// (right, side, king from, king to, rook from, rook to)
+const CASTLING: [(u8, Side, usize, usize, usize, usize); 4] = [
+    (WHITE_KINGSIDE, Side::White, 60, 62, 63, 61),
+    (WHITE_QUEENSIDE, Side::White, 60, 58, 56, 59),
+    (BLACK_KINGSIDE, Side::Black, 4, 6, 7, 5),
+    (BLACK_QUEENSIDE, Side::Black, 4, 2, 0, 3),
+];
+
+// rook (from, to) of the castling move a king makes from `from` to `to`, if it is one
+pub fn castling_rook(from: usize, to: usize) -> Option<(usize, usize)> {
+    CASTLING
+        .iter()
+        .find(|(_, _, king_from, king_to, _, _)| (*king_from == from) & (*king_to == to))
+        .map(|(_, _, _, _, rook_from, rook_to)| (*rook_from, *rook_to))
+}
+
+// rights gone once anything moves from or to `square`
+fn rights_lost(square: usize) -> u8 {
+    CASTLING
+        .iter()
+        .filter(|(_, _, king, _, rook, _)| (*king == square) | (*rook == square))
+        .fold(0, |rights, (right, ..)| rights | right)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitMove {
+    pub from: u8,
+    pub to: u8,
+    pub promotion: Option<PieceType>,
+}
+
+impl BitMove {
+    pub fn from_id(&self) -> CellId {
+        CellId::from_vec_idx(self.from as usize)
+    }
+    pub fn to_id(&self) -> CellId {
+        CellId::from_vec_idx(self.to as usize)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    // indexed by side_index then piece_index
+    pub pieces: [[Bitboard; 6]; 2],
+    pub occupancy: [Bitboard; 2],
+    pub turn: Side,
+    pub castling: u8,
+    // square a pawn may take on en passant
+    pub en_passant: Option<usize>,
+    pub halfmove_clock: u32,
+    // Zobrist key, kept up to date piece by piece and move by move
+    pub hash: u64,
+}
+
+impl Default for Position {
+    fn default() -> Position {
+        Position {
+            pieces: [[0; 6]; 2],
+            occupancy: [0; 2],
+            turn: Side::White,
+            castling: 0,
+            en_passant: None,
+            halfmove_clock: 0,
+            hash: 0,
+        }
+    }
+}
+
+impl Position {
+    pub(crate) fn put(&mut self, square: usize, side: Side, piece_type: PieceType) {
+        self.pieces[side_index(side)][piece_index(piece_type)] |= bit(square);
+        self.occupancy[side_index(side)] |= bit(square);
+        self.hash ^= zobrist::piece(side, piece_type, square);
+    }
+
+    fn remove(&mut self, square: usize, side: Side, piece_type: PieceType) {
+        self.pieces[side_index(side)][piece_index(piece_type)] &= !bit(square);
+        self.occupancy[side_index(side)] &= !bit(square);
+        self.hash ^= zobrist::piece(side, piece_type, square);
+    }
+
+    pub fn bitboard(&self, side: Side, piece_type: PieceType) -> Bitboard {
+        self.pieces[side_index(side)][piece_index(piece_type)]
+    }
+
+    pub fn occupied(&self) -> Bitboard {
+        self.occupancy[0] | self.occupancy[1]
+    }
+
+    pub fn piece_at(&self, square: usize) -> Option<(Side, PieceType)> {
+        let side = [Side::White, Side::Black]
+            .into_iter()
+            .find(|side| self.occupancy[side_index(*side)] & bit(square) != 0)?;
+        PIECE_TYPES
+            .into_iter()
+            .find(|piece_type| self.bitboard(side, *piece_type) & bit(square) != 0)
+            .map(|piece_type| (side, piece_type))
+    }
+
+    pub fn king_square(&self, side: Side) -> usize {
+        self.bitboard(side, PieceType::King).trailing_zeros() as usize
+    }
+
+    // pieces of `by` that have `square` in their line of sight
+    pub fn attackers(&self, square: usize, by: Side, occupied: Bitboard) -> Bitboard {
+        let queens = self.bitboard(by, PieceType::Queen);
+        (KNIGHT_ATTACKS[square] & self.bitboard(by, PieceType::Knight))
+            | (KING_ATTACKS[square] & self.bitboard(by, PieceType::King))
+            | (PAWN_ATTACKS[side_index(by.switch())][square] & self.bitboard(by, PieceType::Pawn))
+            | (rook_attacks(square, occupied) & (self.bitboard(by, PieceType::Rook) | queens))
+            | (bishop_attacks(square, occupied) & (self.bitboard(by, PieceType::Bishop) | queens))
+    }
+
+    pub fn is_attacked(&self, square: usize, by: Side) -> bool {
+        self.attackers(square, by, self.occupied()) != 0
+    }
+
+    // pieces giving check to the side to move
+    pub fn checkers(&self) -> Bitboard {
+        self.attackers(
+            self.king_square(self.turn),
+            self.turn.switch(),
+            self.occupied(),
+        )
+    }
+
+    // anything `side` could mate with, should the other side help: a pawn, rook or
+    // queen, or two minor pieces other than bishops all on squares of one colour.
+    // A lone knight needs an enemy pawn, knight, bishop or rook to block the king
+    // in, bishops of one colour an enemy pawn, knight or bishop of the other colour
+    pub fn has_mating_material(&self, side: Side) -> bool {
+        let heavy = self.bitboard(side, PieceType::Pawn)
+            | self.bitboard(side, PieceType::Rook)
+            | self.bitboard(side, PieceType::Queen);
+        if heavy != 0 {
+            return true;
+        }
+        let knights = self.bitboard(side, PieceType::Knight);
+        let bishops = self.bitboard(side, PieceType::Bishop);
+        let them = side.switch();
+        let blockers =
+            self.bitboard(them, PieceType::Pawn) | self.bitboard(them, PieceType::Knight);
+        if knights == 0 {
+            let (light, dark) = (bishops & LIGHT_SQUARES, bishops & !LIGHT_SQUARES);
+            let other_colour = match (light != 0, dark != 0) {
+                (false, false) => return false,
+                (true, true) => return true,
+                (true, false) => !LIGHT_SQUARES,
+                (false, true) => LIGHT_SQUARES,
+            };
+            return blockers | (self.bitboard(them, PieceType::Bishop) & other_colour) != 0;
+        }
+        if (knights | bishops).count_ones() > 1 {
+            return true;
+        }
+        blockers | self.bitboard(them, PieceType::Bishop) | self.bitboard(them, PieceType::Rook)
+            != 0
+    }
+
+    // no sequence of moves can mate: bare kings, a single minor piece, or only
+    // bishops, all on squares of one colour
+    pub fn is_dead(&self) -> bool {
+        let both = |piece_type| {
+            self.bitboard(Side::White, piece_type) | self.bitboard(Side::Black, piece_type)
+        };
+        if both(PieceType::Pawn) | both(PieceType::Rook) | both(PieceType::Queen) != 0 {
+            return false;
+        }
+        let (knights, bishops) = (both(PieceType::Knight), both(PieceType::Bishop));
+        if (knights | bishops).count_ones() <= 1 {
+            return true;
+        }
+        (knights == 0) && ((bishops & LIGHT_SQUARES == 0) || (bishops & !LIGHT_SQUARES == 0))
+    }
+
+    // Zobrist key from scratch, of what makes two positions the same for
+    // repetition: the pieces, the side to move, castling rights and an en passant
+    // capture; `hash` should always equal it
+    pub fn key(&self) -> u64 {
+        let mut key = self.state_key();
+        for side in [Side::White, Side::Black] {
+            for piece_type in PIECE_TYPES {
+                for square in squares(self.bitboard(side, piece_type)) {
+                    key ^= zobrist::piece(side, piece_type, square);
+                }
+            }
+        }
+        key
+    }
+
+    // the part of the key that is not the pieces; the en passant file only
+    // counts with a pawn beside the one that just made its double step
+    pub fn state_key(&self) -> u64 {
+        let mut key = zobrist::castling(self.castling);
+        if self.turn == Side::Black {
+            key ^= zobrist::BLACK_TO_MOVE;
+        }
+        let en_passant = self.en_passant.filter(|&square| {
+            PAWN_ATTACKS[side_index(self.turn.switch())][square]
+                & self.bitboard(self.turn, PieceType::Pawn)
+                != 0
+        });
+        if let Some(square) = en_passant {
+            key ^= zobrist::en_passant(square);
+        }
+        key
+    }
+
+    pub fn in_check(&self) -> bool {
+        self.checkers() != 0
+    }
+
+    // moves that follow the pieces' movement, not yet minding the mover's own king
+    fn pseudo_moves(&self) -> Vec<BitMove> {
+        let mut moves: Vec<BitMove> = Vec::with_capacity(64);
+        let us = self.turn;
+        let own = self.occupancy[side_index(us)];
+        let enemy = self.occupancy[side_index(us.switch())];
+        let occupied = own | enemy;
+        // the enemy king blocks, but is never taken
+        let own = own | self.bitboard(us.switch(), PieceType::King);
+        let enemy = enemy & !self.bitboard(us.switch(), PieceType::King);
+
+        let mut push = |from: usize, targets: Bitboard, promotes: bool| {
+            for to in squares(targets) {
+                let (from, to) = (from as u8, to as u8);
+                if promotes {
+                    for piece_type in crate::game::Promotion::CHOICES {
+                        moves.push(BitMove {
+                            from,
+                            to,
+                            promotion: Some(piece_type),
+                        });
+                    }
+                } else {
+                    moves.push(BitMove {
+                        from,
+                        to,
+                        promotion: None,
+                    });
+                }
+            }
+        };
+
+        let (start_rank, last_rank) = match us {
+            Side::White => (RANK_2, RANK_8),
+            Side::Black => (RANK_7, RANK_1),
+        };
+        for from in squares(self.bitboard(us, PieceType::Pawn) & !last_rank) {
+            let one = match us {
+                Side::White => from - 8,
+                Side::Black => from + 8,
+            };
+            let mut targets = 0;
+            if occupied & bit(one) == 0 {
+                targets |= bit(one);
+                if start_rank & bit(from) != 0 {
+                    let two = match us {
+                        Side::White => one - 8,
+                        Side::Black => one + 8,
+                    };
+                    targets |= bit(two) & !occupied;
+                }
+            }
+            let ep = self.en_passant.map_or(0, bit);
+            targets |= PAWN_ATTACKS[side_index(us)][from] & (enemy | ep);
+            push(from, targets, targets & last_rank != 0);
+        }
+        for from in squares(self.bitboard(us, PieceType::Knight)) {
+            push(from, KNIGHT_ATTACKS[from] & !own, false);
+        }
+        let queens = self.bitboard(us, PieceType::Queen);
+        for from in squares(self.bitboard(us, PieceType::Bishop) | queens) {
+            push(from, bishop_attacks(from, occupied) & !own, false);
+        }
+        for from in squares(self.bitboard(us, PieceType::Rook) | queens) {
+            push(from, rook_attacks(from, occupied) & !own, false);
+        }
+        let king = self.king_square(us);
+        push(king, KING_ATTACKS[king] & !own, false);
+
+        // castle, never out of or through check; into check is left to the legality filter
+        if self.in_check() {
+            return moves;
+        }
+        for (right, side, king_from, king_to, rook_from, _) in CASTLING {
+            if (side != us) | (self.castling & right == 0) {
+                continue;
+            }
+            let (low, high) = (king_from.min(rook_from), king_from.max(rook_from));
+            let between = (bit(high) - 1) & !(bit(low + 1) - 1);
+            let transit = (king_from + king_to) / 2;
+            if (occupied & between == 0) && !self.is_attacked(transit, us.switch()) {
+                push(king_from, bit(king_to), false);
+            }
+        }
+        moves
+    }
+
+    pub fn legal_moves(&self) -> Vec<BitMove> {
+        let us = self.turn;
+        let mut moves = self.pseudo_moves();
+        moves.retain(|mv| {
+            let next = self.make(mv);
+            !next.is_attacked(next.king_square(us), us.switch())
+        });
+        moves
+    }
+
+    // the position after `mv`, which is taken to be a move of the side to move
+    pub fn make(&self, mv: &BitMove) -> Position {
+        let mut next = *self;
+        let (us, them) = (self.turn, self.turn.switch());
+        let (from, to) = (mv.from as usize, mv.to as usize);
+        let Some((_, piece_type)) = self.piece_at(from) else {
+            return next;
+        };
+        // the pieces put and removed keep the key in step, the rest is swapped whole
+        next.hash ^= self.state_key();
+
+        let mut captured = false;
+        if let Some((_, victim)) = self.piece_at(to) {
+            next.remove(to, them, victim);
+            captured = true;
+        }
+        if (piece_type == PieceType::Pawn) && (Some(to) == self.en_passant) {
+            next.remove((from / 8) * 8 + to % 8, them, PieceType::Pawn);
+            captured = true;
+        }
+        next.remove(from, us, piece_type);
+        next.put(to, us, mv.promotion.unwrap_or(piece_type));
+        if piece_type == PieceType::King {
+            if let Some((rook_from, rook_to)) = castling_rook(from, to) {
+                next.remove(rook_from, us, PieceType::Rook);
+                next.put(rook_to, us, PieceType::Rook);
+            }
+        }
+
+        next.castling &= !(rights_lost(from) | rights_lost(to));
+        next.en_passant = ((piece_type == PieceType::Pawn) && (from.abs_diff(to) == 16))
+            .then_some((from + to) / 2);
+        next.halfmove_clock = if captured || (piece_type == PieceType::Pawn) {
+            0
+        } else {
+            self.halfmove_clock + 1
+        };
+        next.turn = them;
+        next.hash ^= next.state_key();
+        next
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use crate::pieces::Side;
+
+// how the extra time per move is handed out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Increment {
+    // added after every move
+    Fischer,
+    // time used is given back after the move, up to the increment
+    Bronstein,
+    // the clock only starts running once the delay is over
+    Delay,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeControl {
+    pub base: Duration,
+    pub increment: Duration,
+    pub kind: Increment,
+}
+
+impl TimeControl {
+    pub fn new(base_minutes: u64, increment_seconds: u64, kind: Increment) -> TimeControl {
+        TimeControl {
+            base: Duration::from_secs(base_minutes * 60),
+            increment: Duration::from_secs(increment_seconds),
+            kind,
+        }
+    }
+
+    // e.g. "3+2", "5+3 Bronstein" or "10 d5"
+    pub fn label(&self) -> String {
+        let base = self.base.as_secs() / 60;
+        let increment = self.increment.as_secs();
+        match self.kind {
+            Increment::Fischer => format!("{}+{}", base, increment),
+            Increment::Bronstein => format!("{}+{} Bronstein", base, increment),
+            Increment::Delay => format!("{} d{}", base, increment),
+        }
+    }
+}
+
+// both sides' time, run by whoever keeps the game going
+#[derive(Debug, Clone)]
+pub struct Clock {
+    pub control: TimeControl,
+    // white first
+    remaining: [Duration; 2],
+    // time the side to move has used on this move
+    spent: Duration,
+}
+
+fn idx(side: Side) -> usize {
+    match side {
+        Side::White => 0,
+        Side::Black => 1,
+    }
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Clock {
+        Clock {
+            control,
+            remaining: [control.base; 2],
+            spent: Duration::ZERO,
+        }
+    }
+
+    pub fn remaining(&self, side: Side) -> Duration {
+        self.remaining[idx(side)]
+    }
+
+    // delay still to run before the clock of the side to move starts going down
+    pub fn delay_left(&self) -> Duration {
+        match self.control.kind {
+            Increment::Delay => self.control.increment.saturating_sub(self.spent),
+            _ => Duration::ZERO,
+        }
+    }
+
+    pub fn flagged(&self, side: Side) -> bool {
+        self.remaining(side).is_zero()
+    }
+
+    // lets `elapsed` pass on the clock of `side`, true once its flag falls
+    pub fn tick(&mut self, side: Side, elapsed: Duration) -> bool {
+        let charge = match self.control.kind {
+            Increment::Delay => elapsed.saturating_sub(self.delay_left()),
+            _ => elapsed,
+        };
+        self.spent += elapsed;
+        let remaining = &mut self.remaining[idx(side)];
+        *remaining = remaining.saturating_sub(charge);
+        remaining.is_zero()
+    }
+
+    // `side` finished its move
+    pub fn press(&mut self, side: Side) {
+        let bonus = match self.control.kind {
+            Increment::Fischer => self.control.increment,
+            Increment::Bronstein => self.spent.min(self.control.increment),
+            Increment::Delay => Duration::ZERO,
+        };
+        if !self.flagged(side) {
+            self.remaining[idx(side)] += bonus;
+        }
+        self.spent = Duration::ZERO;
+    }
+}
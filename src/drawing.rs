@@ -0,0 +1,93 @@
+use crate::grid::CellId;
+
+// the colours board drawings come in, as PGN comment commands know them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkColor {
+    Green,
+    Red,
+    Yellow,
+    Blue,
+}
+
+impl MarkColor {
+    pub fn letter(self) -> char {
+        match self {
+            MarkColor::Green => 'G',
+            MarkColor::Red => 'R',
+            MarkColor::Yellow => 'Y',
+            MarkColor::Blue => 'B',
+        }
+    }
+}
+
+// arrows and circled squares drawn on a position
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Drawings {
+    pub arrows: Vec<(CellId, CellId, MarkColor)>,
+    pub circles: Vec<(CellId, MarkColor)>,
+}
+
+impl Drawings {
+    pub fn is_empty(&self) -> bool {
+        self.arrows.is_empty() && self.circles.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.arrows.clear();
+        self.circles.clear();
+    }
+
+    pub fn arrow(&self, from: CellId, to: CellId) -> Option<MarkColor> {
+        self.arrows
+            .iter()
+            .find(|(from_, to_, _)| (*from_ == from) && (*to_ == to))
+            .map(|(_, _, color)| *color)
+    }
+
+    // draws the arrow in `color`, over one already there, or rubs it out for None
+    pub fn set_arrow(&mut self, from: CellId, to: CellId, color: Option<MarkColor>) {
+        self.arrows
+            .retain(|(from_, to_, _)| (*from_ != from) || (*to_ != to));
+        if let Some(color) = color {
+            self.arrows.push((from, to, color));
+        }
+    }
+
+    pub fn circle(&self, square: CellId) -> Option<MarkColor> {
+        self.circles
+            .iter()
+            .find(|(square_, _)| *square_ == square)
+            .map(|(_, color)| *color)
+    }
+
+    pub fn set_circle(&mut self, square: CellId, color: Option<MarkColor>) {
+        self.circles.retain(|(square_, _)| *square_ != square);
+        if let Some(color) = color {
+            self.circles.push((square, color));
+        }
+    }
+
+    // the `[%csl]` and `[%cal]` commands for a PGN comment, e.g. "[%csl Rd5][%cal Ge2e4]"
+    pub fn to_commands(&self) -> String {
+        let mut commands = String::new();
+        if !self.circles.is_empty() {
+            let circles: Vec<String> = self
+                .circles
+                .iter()
+                .map(|(square, color)| format!("{}{}", color.letter(), square.to_square()))
+                .collect();
+            commands.push_str(&format!("[%csl {}]", circles.join(",")));
+        }
+        if !self.arrows.is_empty() {
+            let arrows: Vec<String> = self
+                .arrows
+                .iter()
+                .map(|(from, to, color)| {
+                    format!("{}{}{}", color.letter(), from.to_square(), to.to_square())
+                })
+                .collect();
+            commands.push_str(&format!("[%cal {}]", arrows.join(",")));
+        }
+        commands
+    }
+}
@@ -0,0 +1,246 @@
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+use crate::game::{Game, STARTING_FEN};
+use crate::grid::CellId;
+use crate::pieces::PieceType;
+use crate::san;
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum EngineError {
+    Io(io::Error),
+    Exited,
+    Timeout(&'static str),
+    BadMove(String),
+    NotReady,
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EngineError::Io(err) => write!(f, "{}", err),
+            EngineError::Exited => write!(f, "engine exited"),
+            EngineError::Timeout(reply) => write!(f, "engine did not answer with '{}'", reply),
+            EngineError::BadMove(reply) => write!(f, "engine sent '{}'", reply),
+            EngineError::NotReady => write!(f, "engine is still starting"),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+impl From<io::Error> for EngineError {
+    fn from(err: io::Error) -> EngineError {
+        EngineError::Io(err)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Reply {
+    // the `uci` and `isready` handshake is still going on
+    Starting,
+    // the handshake is done, given once; searches can start
+    Ready,
+    Thinking,
+    BestMove(CellId, CellId, Option<PieceType>),
+    // `bestmove 0000` or `(none)`, there was nothing to play
+    NoMove,
+}
+
+// how far the engine has got in starting up
+#[derive(PartialEq)]
+enum Handshake {
+    Uci,
+    IsReady,
+    Ready,
+}
+
+// a UCI engine running as a child process
+pub struct Engine {
+    // taken when the engine is dropped, to be waited for on a thread of its own
+    child: Option<Child>,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    pub name: String,
+    handshake: Handshake,
+    // when the awaited handshake reply is overdue
+    deadline: Instant,
+    searching: bool,
+    // answers still to come for searches that were called off
+    stale: u32,
+}
+
+impl Engine {
+    // starts the program and says `uci`; `poll` goes on with the handshake, so
+    // the caller is never held up by a slow or silent engine
+    pub fn spawn(program: &str, args: &[&str]) -> Result<Engine, EngineError> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (sender, lines) = channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut engine = Engine {
+            child: Some(child),
+            stdin,
+            lines,
+            name: program.to_string(),
+            handshake: Handshake::Uci,
+            deadline: Instant::now() + HANDSHAKE_TIMEOUT,
+            searching: false,
+            stale: 0,
+        };
+        engine.send("uci")?;
+        Ok(engine)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.handshake == Handshake::Ready
+    }
+
+    fn send(&mut self, line: &str) -> Result<(), EngineError> {
+        writeln!(self.stdin, "{}", line)?;
+        self.stdin.flush()?;
+        Ok(())
+    }
+
+    // reads what came in of the handshake, `uciok` then `readyok`
+    fn start_up(&mut self) -> Result<Reply, EngineError> {
+        loop {
+            let line = match self.lines.try_recv() {
+                Ok(line) => line,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Err(EngineError::Exited),
+            };
+            if let Some(name) = line.strip_prefix("id name ") {
+                self.name = name.trim().to_string();
+            }
+            match (&self.handshake, line.trim()) {
+                (Handshake::Uci, "uciok") => {
+                    self.send("isready")?;
+                    self.handshake = Handshake::IsReady;
+                    self.deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+                }
+                (Handshake::IsReady, "readyok") => {
+                    self.handshake = Handshake::Ready;
+                    return Ok(Reply::Ready);
+                }
+                _ => {}
+            }
+        }
+        if Instant::now() < self.deadline {
+            return Ok(Reply::Starting);
+        }
+        Err(EngineError::Timeout(match self.handshake {
+            Handshake::Uci => "uciok",
+            _ => "readyok",
+        }))
+    }
+
+    // sets up the game's position, from its start and through its moves, and starts a search
+    pub fn go(&mut self, game: &Game, movetime: Duration) -> Result<(), EngineError> {
+        if !self.is_ready() {
+            return Err(EngineError::NotReady);
+        }
+        self.stop()?;
+        let mut position = if game.start_fen == STARTING_FEN {
+            "position startpos".to_string()
+        } else {
+            format!("position fen {}", game.start_fen)
+        };
+        if !game.history.is_empty() {
+            position.push_str(" moves");
+            for played in &game.history {
+                position.push(' ');
+                position.push_str(&san::coordinate(&played.from, &played.to, played.promotion));
+            }
+        }
+        self.send(&position)?;
+        self.send(&format!("go movetime {}", movetime.as_millis()))?;
+        self.searching = true;
+        Ok(())
+    }
+
+    // calls off the search; its answer will be thrown away
+    pub fn stop(&mut self) -> Result<(), EngineError> {
+        if self.searching {
+            self.searching = false;
+            self.stale += 1;
+            self.send("stop")?;
+        }
+        Ok(())
+    }
+
+    // how the handshake is going, then the answer to the last `go`, without blocking
+    pub fn poll(&mut self) -> Result<Reply, EngineError> {
+        if !self.is_ready() {
+            return self.start_up();
+        }
+        loop {
+            let line = match self.lines.try_recv() {
+                Ok(line) => line,
+                Err(TryRecvError::Empty) => return Ok(Reply::Thinking),
+                Err(TryRecvError::Disconnected) => return Err(EngineError::Exited),
+            };
+            let mut words = line.split_whitespace();
+            if words.next() != Some("bestmove") {
+                continue;
+            }
+            if self.stale > 0 {
+                self.stale -= 1;
+                continue;
+            }
+            self.searching = false;
+            return match words.next() {
+                Some("0000") | Some("(none)") => Ok(Reply::NoMove),
+                Some(notation) => match san::parse_coordinate(notation) {
+                    Some((from, to, promotion)) => Ok(Reply::BestMove(from, to, promotion)),
+                    None => Err(EngineError::BadMove(line.clone())),
+                },
+                None => Err(EngineError::BadMove(line.clone())),
+            };
+        }
+    }
+}
+
+const QUIT_TIMEOUT: Duration = Duration::from_millis(500);
+
+impl Drop for Engine {
+    // gives the engine a moment to quit on its own before it is killed, waiting
+    // on a thread of its own so the caller carries on at once
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let Some(mut child) = self.child.take() else {
+            return;
+        };
+        std::thread::spawn(move || {
+            let started = Instant::now();
+            while started.elapsed() < QUIT_TIMEOUT {
+                if !matches!(child.try_wait(), Ok(None)) {
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+        });
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt;
+
+use crate::bitboard::{Position, BLACK_KINGSIDE, BLACK_QUEENSIDE, WHITE_KINGSIDE, WHITE_QUEENSIDE};
+use crate::game::Game;
+use crate::grid::{CellId, Grid};
+use crate::pieces::{Piece, PieceType, Side};
+
+#[derive(Debug, PartialEq)]
+pub enum FenError {
+    MissingField(&'static str),
+    TooManyFields,
+    RankCount(usize),
+    RankLength { rank: u32, squares: u32 },
+    InvalidPiece(char),
+    KingCount { side: Side, count: usize },
+    InvalidSide(String),
+    InvalidCastling(String),
+    InvalidEnPassant(String),
+    InvalidCounter { field: &'static str, value: String },
+    // the side that just moved left its king en prise
+    OpponentInCheck(Side),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FenError::MissingField(field) => write!(f, "missing {} field", field),
+            FenError::TooManyFields => write!(f, "more than six fields"),
+            FenError::RankCount(n) => write!(f, "expected 8 ranks, found {}", n),
+            FenError::RankLength { rank, squares } => {
+                write!(
+                    f,
+                    "rank {} describes {} squares instead of 8",
+                    rank, squares
+                )
+            }
+            FenError::InvalidPiece(c) => write!(f, "'{}' is not a piece", c),
+            FenError::KingCount { side, count } => {
+                write!(f, "{:?} has {} kings, expected exactly one", side, count)
+            }
+            FenError::InvalidSide(s) => write!(f, "side to move must be w or b, found '{}'", s),
+            FenError::InvalidCastling(s) => write!(f, "invalid castling rights '{}'", s),
+            FenError::InvalidEnPassant(s) => write!(f, "invalid en passant square '{}'", s),
+            FenError::InvalidCounter { field, value } => {
+                write!(f, "{} must be a number, found '{}'", field, value)
+            }
+            FenError::OpponentInCheck(side) => {
+                write!(f, "{:?} is in check but it is not its move", side)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+// the right, king square and rook square for each castling letter
+fn castling_squares(c: char) -> Option<(u8, Side, CellId, CellId)> {
+    match c {
+        'K' => Some((WHITE_KINGSIDE, Side::White, CellId(4, 7), CellId(7, 7))),
+        'Q' => Some((WHITE_QUEENSIDE, Side::White, CellId(4, 7), CellId(0, 7))),
+        'k' => Some((BLACK_KINGSIDE, Side::Black, CellId(4, 0), CellId(7, 0))),
+        'q' => Some((BLACK_QUEENSIDE, Side::Black, CellId(4, 0), CellId(0, 0))),
+        _ => None,
+    }
+}
+
+fn piece_char(piece: &Piece) -> char {
+    let c = piece.piece_type.to_char();
+    match piece.side {
+        Side::White => c.to_ascii_uppercase(),
+        Side::Black => c,
+    }
+}
+
+fn parse_counter(field: &'static str, value: Option<&str>, default: u32) -> Result<u32, FenError> {
+    let Some(value) = value else {
+        return Ok(default);
+    };
+    value.parse().map_err(|_| FenError::InvalidCounter {
+        field,
+        value: value.to_string(),
+    })
+}
+
+impl Game {
+    pub fn from_fen(grid: &mut Grid, fen: &str) -> Result<Game, FenError> {
+        let mut fields = fen.split_whitespace();
+        let placement = fields
+            .next()
+            .ok_or(FenError::MissingField("piece placement"))?;
+        let side = fields
+            .next()
+            .ok_or(FenError::MissingField("side to move"))?;
+        let castling = fields.next().ok_or(FenError::MissingField("castling"))?;
+        let en_passant = fields.next().ok_or(FenError::MissingField("en passant"))?;
+        let halfmove_clock = parse_counter("halfmove clock", fields.next(), 0)?;
+        let fullmove = parse_counter("fullmove number", fields.next(), 1)?;
+        if fields.next().is_some() {
+            return Err(FenError::TooManyFields);
+        }
+
+        let ranks: Vec<&str> = placement.split('/').collect();
+        if ranks.len() != 8 {
+            return Err(FenError::RankCount(ranks.len()));
+        }
+        let mut pieces: Vec<(CellId, PieceType, Side)> = Vec::new();
+        for (y, rank) in ranks.iter().enumerate() {
+            let y = y as u32;
+            let mut x: u32 = 0;
+            for c in rank.chars() {
+                if let Some(n) = c.to_digit(10) {
+                    x += n;
+                    continue;
+                }
+                let piece_type = PieceType::from_char(c).ok_or(FenError::InvalidPiece(c))?;
+                let side = if c.is_ascii_uppercase() {
+                    Side::White
+                } else {
+                    Side::Black
+                };
+                if x < 8 {
+                    pieces.push((CellId(x, y), piece_type, side));
+                }
+                x += 1;
+            }
+            if x != 8 {
+                return Err(FenError::RankLength {
+                    rank: 8 - y,
+                    squares: x,
+                });
+            }
+        }
+        let piece_at = |id: CellId| {
+            pieces
+                .iter()
+                .find(|(cell, _, _)| *cell == id)
+                .map(|(_, piece_type, side)| (*piece_type, *side))
+        };
+
+        for side in [Side::White, Side::Black] {
+            let count = pieces
+                .iter()
+                .filter(|(_, piece_type, s)| *piece_type == PieceType::King && *s == side)
+                .count();
+            if count != 1 {
+                return Err(FenError::KingCount { side, count });
+            }
+        }
+
+        let turn = match side {
+            "w" => Side::White,
+            "b" => Side::Black,
+            _ => return Err(FenError::InvalidSide(side.to_string())),
+        };
+        // plies played before the position, which has to fit the move counter
+        let move_count = (fullmove.max(1) - 1)
+            .checked_mul(2)
+            .and_then(|plies| plies.checked_add(if turn == Side::Black { 1 } else { 0 }))
+            .ok_or_else(|| FenError::InvalidCounter {
+                field: "fullmove number",
+                value: fullmove.to_string(),
+            })?;
+        let mut position = Position {
+            turn,
+            halfmove_clock,
+            ..Default::default()
+        };
+        for (id, piece_type, side) in &pieces {
+            position.put(id.to_vec_idx(), *side, *piece_type);
+        }
+
+        // a right needs its king and rook still at home
+        if castling != "-" {
+            for c in castling.chars() {
+                let invalid = || FenError::InvalidCastling(castling.to_string());
+                let (right, side, king, rook) = castling_squares(c).ok_or_else(invalid)?;
+                if (piece_at(king) != Some((PieceType::King, side)))
+                    | (piece_at(rook) != Some((PieceType::Rook, side)))
+                {
+                    return Err(invalid());
+                }
+                position.castling |= right;
+            }
+        }
+
+        // the square behind a pawn that just made a double step
+        if en_passant != "-" {
+            let invalid = || FenError::InvalidEnPassant(en_passant.to_string());
+            let dest = CellId::from_square(en_passant).ok_or_else(invalid)?;
+            let (target_rank, pawn_side, pawn_rank) = match turn {
+                Side::White => (2, Side::Black, 3),
+                Side::Black => (5, Side::White, 4),
+            };
+            if dest.1 != target_rank {
+                return Err(invalid());
+            }
+            let pawn = CellId(dest.0, pawn_rank);
+            if (piece_at(pawn) != Some((PieceType::Pawn, pawn_side))) | piece_at(dest).is_some() {
+                return Err(invalid());
+            }
+            position.en_passant = Some(dest.to_vec_idx());
+        }
+
+        position.hash = position.key();
+
+        // a king that could be taken right away is no position to play on
+        let opponent = turn.switch();
+        if position.is_attacked(position.king_square(opponent), turn) {
+            return Err(FenError::OpponentInCheck(opponent));
+        }
+
+        let mut game = Game {
+            white_stack: Vec::new(),
+            black_stack: Vec::new(),
+            checked: None,
+            position,
+            move_count,
+            promotion: None,
+            result: None,
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+            start_fen: fen.split_whitespace().collect::<Vec<&str>>().join(" "),
+            clock: None,
+            position_history: Vec::new(),
+            start_drawings: Default::default(),
+        };
+        game.update_status(grid);
+        game.record_position();
+        Ok(game)
+    }
+
+    pub fn to_fen(&self) -> String {
+        let mut placement = String::new();
+        for y in 0..8 {
+            let mut empty = 0;
+            for x in 0..8 {
+                let Some((side, piece_type)) = self.position.piece_at(CellId(x, y).to_vec_idx())
+                else {
+                    empty += 1;
+                    continue;
+                };
+                if empty > 0 {
+                    placement.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                placement.push(piece_char(&Piece::new(piece_type, side)));
+            }
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+            }
+            if y < 7 {
+                placement.push('/');
+            }
+        }
+
+        let side = match self.turn() {
+            Side::White => "w",
+            Side::Black => "b",
+        };
+
+        let mut castling = String::new();
+        for c in ['K', 'Q', 'k', 'q'] {
+            let (right, ..) = castling_squares(c).unwrap();
+            if self.position.castling & right != 0 {
+                castling.push(c);
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let en_passant = match self.position.en_passant {
+            Some(square) => CellId::from_vec_idx(square).to_square(),
+            None => "-".to_string(),
+        };
+
+        format!(
+            "{} {} {} {} {} {}",
+            placement,
+            side,
+            castling,
+            en_passant,
+            self.halfmove_clock(),
+            self.move_count / 2 + 1
+        )
+    }
+}
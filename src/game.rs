@@ -1,31 +1,82 @@
-use crate::grid::{Cell, CellId, Grid};
-use crate::path::{Direction, Magnitude, Path};
+use crate::bitboard::{BitMove, Position};
+use crate::clock::Clock;
+use crate::drawing::Drawings;
+use crate::grid::{CellId, Grid};
+use crate::path::Direction;
 use crate::pieces::{Piece, PieceType, Side};
-use crate::textures::PieceTxts;
+use crate::san;
+use std::time::Duration;
 
-pub struct BoardStatus {
-    pinned_pieces: Vec<(CellId, Path)>,
-    checks: Vec<Path>,
-}
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
 
 #[derive(Debug)]
 pub struct Check {
+    // more than one piece gives check
     pub absolute: bool,
-    pub path: Path,
+    pub king: CellId,
+}
+#[derive(Debug, PartialEq)]
+pub enum GameResult {
+    Checkmate { winner: Side },
+    Stalemate,
+    // flag fall against a side that still has mating material
+    Timeout { winner: Side },
+    Draw(DrawReason),
+}
+impl GameResult {
+    pub fn winner(&self) -> Option<Side> {
+        match self {
+            GameResult::Checkmate { winner } | GameResult::Timeout { winner } => Some(*winner),
+            GameResult::Stalemate | GameResult::Draw(_) => None,
+        }
+    }
+}
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DrawReason {
+    // flag fall, but the other side has nothing left to mate with
+    TimeoutVsInsufficientMaterial,
+    // neither side can mate any more
+    InsufficientMaterial,
+    FivefoldRepetition,
+    SeventyFiveMoves,
+    // claimed by a player
+    ThreefoldRepetition,
+    FiftyMoves,
+}
+// one played ply as recorded in the game history, with what it takes to take it back
+#[derive(Debug, Clone)]
+pub struct Move {
+    pub from: CellId,
+    pub to: CellId,
+    pub promotion: Option<PieceType>,
+    pub san: String,
+    pub ply: u32,
+    // square of the captured piece, which sits on top of its stack; differs from `to` en passant
+    pub captured: Option<CellId>,
+    // the position before the move, key included, put back by undo
+    pub position: Position,
+    // drawn on the position the move leads to
+    pub drawings: Drawings,
 }
 #[derive(Debug)]
 pub struct Game {
     pub white_stack: Vec<Piece>,
     pub black_stack: Vec<Piece>,
-    pub turn: Side,
-    pub cell_cache: Vec<CellId>,
-    pub white_king: CellId,
-    pub black_king: CellId,
     pub checked: Option<Check>,
+    // the pieces, side to move, castling rights and en passant square; the grid
+    // only shows it
+    pub position: Position,
     pub move_count: u32,
-    pub en_passant: Option<EnPassant>,
-    pub castles: Vec<Castle>,
-    pub last_played: Option<(CellId, CellId)>,
+    pub promotion: Option<Promotion>,
+    pub result: Option<GameResult>,
+    pub history: Vec<Move>,
+    pub redo_stack: Vec<Move>,
+    pub start_fen: String,
+    pub clock: Option<Clock>,
+    // keys of every position of the game so far, the current one last
+    pub position_history: Vec<u64>,
+    // drawn on the starting position, later ones go with their move
+    pub start_drawings: Drawings,
 }
 
 impl Game {
@@ -35,230 +86,373 @@ impl Game {
             Side::White => self.white_stack.push(piece),
         }
     }
-    pub fn king_now(&self) -> &CellId {
-        match self.turn {
-            Side::White => &self.white_king,
-            Side::Black => &self.black_king,
-        }
+    pub fn turn(&self) -> Side {
+        self.position.turn
+    }
+    pub fn halfmove_clock(&self) -> u32 {
+        self.position.halfmove_clock
+    }
+    pub fn king_now(&self) -> CellId {
+        self.king_of(self.turn())
+    }
+    pub fn king_of(&self, side: Side) -> CellId {
+        CellId::from_vec_idx(self.position.king_square(side))
+    }
+    // from and to of the last move, a promotion still to be picked included
+    pub fn last_played(&self) -> Option<(CellId, CellId)> {
+        self.history.last().map(|played| (played.from, played.to))
+    }
+    pub fn new(grid: &mut Grid) -> Game {
+        Game::from_fen(grid, STARTING_FEN).unwrap()
     }
-    pub fn new(grid: &mut Grid, piecetxts: &'static PieceTxts) -> Game {
-        let starting_pos =
-            "wra1 wnb1 wbc1 wqd1 wke1 wbf1 wng1 wrh1 wpa2 wpb2 wpc2 wpd2 wpe2 wpf2 wpg2 wph2 bra8 bnb8 bbc8 bqd8 bke8 bbf8 bng8 brh8 bpa7 bpb7 bpc7 bpd7 bpe7 bpf7 bpg7 bph7";
 
-        for code in starting_pos.split_whitespace() {
-            let alg = AlgebraicNotation::new(code);
-            grid.get_cell_mut(&alg.cell)
-                .add_item(Piece::new(alg.piece, piecetxts, alg.side));
+    // plays a move for the side to move; a promoting pawn then waits for Game::promote
+    pub fn play(&mut self, grid: &mut Grid, from: &CellId, to: &CellId) -> bool {
+        if self.result.is_some() || self.promotion.is_some() {
+            return false;
         }
-        Game {
-            white_stack: Vec::new(),
-            black_stack: Vec::new(),
-            turn: Side::White,
-            cell_cache: Vec::new(),
-            white_king: CellId(4, 7),
-            black_king: CellId(4, 0),
-            checked: None,
-            move_count: 0,
-            en_passant: None,
-            last_played: None,
-            castles: Vec::new(),
+        let cell = grid.get_cell(from);
+        let (Some(piece), Some(valid_moves)) = (cell.item, &cell.valid_moves) else {
+            return false;
+        };
+        if (piece.side != self.turn()) || !valid_moves.contains(to) {
+            return false;
         }
+        let captured = if grid.get_cell(to).item.is_some() {
+            Some(*to)
+        } else if (piece.piece_type == PieceType::Pawn) && (from.0 != to.0) {
+            Some(CellId(to.0, from.1))
+        } else {
+            None
+        };
+        self.history.push(Move {
+            from: *from,
+            to: *to,
+            promotion: None,
+            san: san::describe(grid, from, to),
+            ply: self.move_count,
+            captured,
+            position: self.position,
+            drawings: Drawings::default(),
+        });
+        self.redo_stack.clear();
+        if Promotion::is(&piece, to) {
+            // the pawn waits on the last rank, the position moves on once its piece is picked
+            grid.get_cell_mut(from).item = None;
+            grid.get_cell_mut(to).item = Some(piece);
+            grid.clear_valid_moves();
+            self.promotion = Some(Promotion {
+                cell: *to,
+                side: piece.side,
+            });
+            return true;
+        }
+        self.apply(grid, None);
+        true
     }
 
-    pub fn switch_turns(&mut self, grid: &mut Grid) {
-        let last_played = self.last_played.unwrap();
-        if let Some(en_passant) = &self.en_passant.take() {
-            en_passant
-                .is(&last_played.0, &last_played.1)
-                .then(|| en_passant.execute(grid, self));
-        }
-        for castle in self.castles.iter() {
-            if castle.is(&last_played.0, &last_played.1) {
-                castle.execute(grid);
-                break;
-            }
+    pub fn promote(&mut self, grid: &mut Grid, piece_type: PieceType) -> bool {
+        if self.promotion.is_none() || !Promotion::CHOICES.contains(&piece_type) {
+            return false;
         }
+        self.promotion = None;
+        let last = self.history.last_mut().unwrap();
+        last.promotion = Some(piece_type);
+        last.san.push('=');
+        last.san.push(san::piece_letter(piece_type));
+        self.apply(grid, Some(piece_type));
+        true
+    }
 
-        self.turn = self.turn.switch();
-        self.move_count += 1;
-        for id in &self.cell_cache {
-            let cell = grid.get_cell_mut(id);
-            cell.valid_moves = None;
-            cell.pin = None;
+    // makes the last move of the history on the position and ends the turn
+    fn apply(&mut self, grid: &mut Grid, promotion: Option<PieceType>) {
+        let played = self.history.last().unwrap();
+        let (from, to, captured) = (played.from, played.to, played.captured);
+        if let Some(square) = captured {
+            let (side, piece_type) = self.position.piece_at(square.to_vec_idx()).unwrap();
+            self.move_to_stack(Piece::new(piece_type, side));
         }
-        self.cell_cache.clear();
-        self.castles.clear();
-
-        let status = self.get_board_status(grid).unwrap();
-        if !status.checks.is_empty() {
-            self.checked = Some(Check {
-                absolute: status.checks.len() > 1,
-                path: *status.checks.first().unwrap(),
-            });
-        } else {
-            self.checked = None;
+        self.position = self.position.make(&BitMove {
+            from: from.to_vec_idx() as u8,
+            to: to.to_vec_idx() as u8,
+            promotion,
+        });
+        if let Some(clock) = &mut self.clock {
+            clock.press(self.position.turn.switch());
         }
-        for (id, path) in status.pinned_pieces {
-            let cell = grid.get_cell_mut(&id);
-            cell.pin = Some(path);
-            self.cell_cache.push(id);
+        self.move_count += 1;
+        self.update_status(grid);
+        self.record_position();
+        let suffix = match (&self.result, &self.checked) {
+            (Some(GameResult::Checkmate { .. }), _) => "#",
+            (_, Some(_)) => "+",
+            _ => "",
+        };
+        self.history.last_mut().unwrap().san.push_str(suffix);
+    }
+
+    // takes back the last ply, including one waiting for its promotion choice
+    pub fn undo(&mut self, grid: &mut Grid) -> bool {
+        let Some(played) = self.history.pop() else {
+            return false;
+        };
+        // a pawn waiting for its piece never left the position
+        if self.promotion.take().is_none() {
+            if played.captured.is_some() {
+                match played.position.turn {
+                    Side::White => self.black_stack.pop(),
+                    Side::Black => self.white_stack.pop(),
+                };
+            }
+            self.position = played.position;
+            self.move_count -= 1;
+            self.position_history.pop();
         }
+        self.result = None;
+        self.update_status(grid);
+        debug_assert_eq!(self.position.hash, self.position.key());
+        self.redo_stack.push(played);
+        true
     }
-    fn get_board_status(&self, grid: &mut Grid) -> Option<BoardStatus> {
-        let king_cell: CellId = match self.turn {
-            Side::White => self.white_king,
-            Side::Black => self.black_king,
+
+    pub fn redo(&mut self, grid: &mut Grid) -> bool {
+        let Some(played) = self.redo_stack.pop() else {
+            return false;
         };
-        let Some(king) = &grid.get_cell(&king_cell).item else {
-            println!("{},{}", king_cell.0, king_cell.1);
-            println!("no king");
-            return None;
+        let redo_stack = std::mem::take(&mut self.redo_stack);
+        let done = match played.promotion {
+            Some(_) => self.make_move(grid, &played.from, &played.to, played.promotion),
+            // also a pawn taken back before its piece was picked, which waits for it again
+            None => self.play(grid, &played.from, &played.to),
         };
-        let mut pinned_pieces: Vec<(CellId, Path)> = Vec::new();
-        let mut checks: Vec<Path> = Vec::new();
-        let side = &king.side;
-        for direction in Direction::iterator() {
-            let mut temp: Option<(CellId, Path)> = None;
-            let mut current_cell = king_cell;
-            let mut n = 0;
+        if done {
+            self.history.last_mut().unwrap().drawings = played.drawings;
+        }
+        self.redo_stack = redo_stack;
+        done
+    }
 
-            while n < 10 {
-                n += 1;
-                let Some(id) = &current_cell.try_next_cellid(*direction, 1) else {
-                    break;
-                };
+    // plays a move with its promotion piece given up front, as engines and files do
+    pub fn make_move(
+        &mut self,
+        grid: &mut Grid,
+        from: &CellId,
+        to: &CellId,
+        promotion: Option<PieceType>,
+    ) -> bool {
+        if !self.play(grid, from, to) {
+            return false;
+        }
+        if self.promotion.is_none() {
+            return true;
+        }
+        if promotion.is_some_and(|piece_type| self.promote(grid, piece_type)) {
+            return true;
+        }
+        // no valid promotion piece, take the pawn back
+        self.undo(grid);
+        self.redo_stack.pop();
+        false
+    }
 
-                current_cell = *id;
-                let cell = grid.get_cell(id);
-                let Some(piece) = &cell.item else {
-                    continue;
-                };
-                let path = Path {
-                    magnitude: Magnitude::Fixed(n),
-                    direction: *direction,
-                };
+    // the drawings of the position on the board
+    pub fn drawings_mut(&mut self) -> &mut Drawings {
+        match self.history.last_mut() {
+            Some(played) => &mut played.drawings,
+            None => &mut self.start_drawings,
+        }
+    }
 
-                if piece.side == *side {
-                    match temp {
-                        Some(_) => break,
-                        None => {
-                            temp = Some((current_cell, path));
-                            continue;
-                        }
-                    }
-                } else {
-                    match temp {
-                        Some(_) => {
-                            for line_of_sight in piece.line_of_sight {
-                                if line_of_sight.is_equal_to(&path.flip()) {
-                                    let Some(path_from_pinned) =
-                                        &path.same_direction_subtract(&temp.unwrap().1)
-                                    else {
-                                        println!("path direction not same ");
-                                        break;
-                                    };
-                                    pinned_pieces.push((temp.unwrap().0, *path_from_pinned));
-                                }
-                            }
-                            break;
-                        }
-                        None => {
-                            for line_of_sight in piece.line_of_sight {
-                                if line_of_sight.is_equal_to(&path.flip()) {
-                                    checks.push(path);
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    // the game as it stood after its first `plies` moves, played out again from its start
+    pub fn at_ply(&self, grid: &mut Grid, plies: usize) -> Game {
+        let mut game = Game::from_fen(grid, &self.start_fen).unwrap();
+        for played in self.history.iter().take(plies) {
+            game.make_move(grid, &played.from, &played.to, played.promotion);
         }
-        Some(BoardStatus {
-            pinned_pieces,
-            checks,
-        })
+        game
     }
-}
-pub struct AlgebraicNotation {
-    pub piece: PieceType,
-    pub cell: CellId,
-    pub side: Side,
-}
 
-impl AlgebraicNotation {
-    pub fn new(code: &str) -> Self {
-        let str_array: Vec<char> = code.chars().collect();
-        let (side_, piece_, horiz, vert) = (str_array[0], str_array[1], str_array[2], str_array[3]);
-        let side = match side_ {
-            'b' => Side::Black,
-            'w' => Side::White,
-            _ => Side::Black,
+    // every valid move of the side to move, a promotion counting once per piece choice
+    pub fn all_valid_moves(&self) -> Vec<(CellId, CellId, Option<PieceType>)> {
+        self.position
+            .legal_moves()
+            .iter()
+            .map(|played| (played.from_id(), played.to_id(), played.promotion))
+            .collect()
+    }
+
+    // runs the clock of the side to move; its flag falling loses the game, or draws
+    // it when the other side has nothing left to mate with
+    pub fn tick(&mut self, elapsed: Duration) {
+        if self.result.is_some() {
+            return;
+        }
+        let Some(clock) = &mut self.clock else {
+            return;
         };
-        let piece = match piece_ {
-            'k' => PieceType::King,
-            'q' => PieceType::Queen,
-            'n' => PieceType::Knight,
-            'b' => PieceType::Bishop,
-            'r' => PieceType::Rook,
-            'p' => PieceType::Pawn,
-            _ => {
-                println!("wrong code for piece");
-                PieceType::Pawn
-            }
+        clock.tick(self.position.turn, elapsed);
+        // the flag may have fallen before a takeback, too
+        let Some(loser) = [Side::White, Side::Black]
+            .into_iter()
+            .find(|side| clock.flagged(*side))
+        else {
+            return;
         };
-        let x: u32 = match horiz {
-            'a' => 0,
-            'b' => 1,
-            'c' => 2,
-            'd' => 3,
-            'e' => 4,
-            'f' => 5,
-            'g' => 6,
-            'h' => 7,
-            _ => 0,
+        let winner = loser.switch();
+        self.result = Some(if self.position.has_mating_material(winner) {
+            GameResult::Timeout { winner }
+        } else {
+            GameResult::Draw(DrawReason::TimeoutVsInsufficientMaterial)
+        });
+    }
+
+    // how often the current position has stood on the board, itself included;
+    // nothing before the last capture or pawn move can come back
+    pub fn repetitions(&self) -> usize {
+        let Some(current) = self.position_history.last() else {
+            return 0;
         };
+        self.position_history
+            .iter()
+            .rev()
+            .take(self.halfmove_clock() as usize + 1)
+            .filter(|key| *key == current)
+            .count()
+    }
 
-        let y: u32 = 8 - vert.to_digit(10).unwrap();
-        let cell = CellId(x, y);
+    // a draw the side to move may claim instead of playing on
+    pub fn claimable_draw(&self) -> Option<DrawReason> {
+        if self.result.is_some() || self.promotion.is_some() {
+            None
+        } else if self.repetitions() >= 3 {
+            Some(DrawReason::ThreefoldRepetition)
+        } else if self.halfmove_clock() >= 100 {
+            Some(DrawReason::FiftyMoves)
+        } else {
+            None
+        }
+    }
 
-        Self { side, piece, cell }
+    pub fn claim_draw(&mut self) -> bool {
+        let Some(reason) = self.claimable_draw() else {
+            return false;
+        };
+        self.result = Some(GameResult::Draw(reason));
+        true
     }
-}
-#[derive(Debug)]
-pub struct EnPassant {
-    pub dest: CellId,
-    pub current: CellId,
-    pub linked_pawn: CellId,
-}
-impl EnPassant {
-    pub fn is(&self, current_cell: &CellId, dest_cell: &CellId) -> bool {
-        (*current_cell == self.current) && (self.dest == *dest_cell)
+
+    // notes the position just reached and ends the game on a draw nobody has to claim
+    pub(crate) fn record_position(&mut self) {
+        debug_assert_eq!(
+            self.position.hash,
+            self.position.key(),
+            "Zobrist key out of step"
+        );
+        self.position_history.push(self.position.hash);
+        if self.result.is_some() {
+            return;
+        }
+        let reason = if self.position.is_dead() {
+            DrawReason::InsufficientMaterial
+        } else if self.repetitions() >= 5 {
+            DrawReason::FivefoldRepetition
+        } else if self.halfmove_clock() >= 150 {
+            DrawReason::SeventyFiveMoves
+        } else {
+            return;
+        };
+        self.result = Some(GameResult::Draw(reason));
     }
-    pub fn execute(&self, grid: &mut Grid, game: &mut Game) {
-        let linked_cell: &mut Cell = grid.get_cell_mut(&self.linked_pawn);
-        game.move_to_stack(linked_cell.item.take().unwrap());
+
+    // shows the position on the grid and works out checks, valid moves and
+    // the result for the side to move
+    pub(crate) fn update_status(&mut self, grid: &mut Grid) {
+        grid.show(&self.position);
+        let checks = self.position.checkers().count_ones();
+        self.checked = (checks > 0).then_some(Check {
+            absolute: checks > 1,
+            king: self.king_now(),
+        });
+
+        if self.calc_all_valid_moves(grid) == 0 {
+            self.result = Some(match self.checked {
+                Some(_) => GameResult::Checkmate {
+                    winner: self.turn().switch(),
+                },
+                None => GameResult::Stalemate,
+            });
+        }
+    }
+
+    // fills in valid_moves for every piece of the side to move, returns the total count
+    fn calc_all_valid_moves(&mut self, grid: &mut Grid) -> usize {
+        let mut valid_moves: Vec<Vec<CellId>> = vec![Vec::new(); 64];
+        let mut count = 0;
+        // a promotion is one destination here, the piece is picked afterwards
+        for played in self.position.legal_moves() {
+            if played
+                .promotion
+                .is_some_and(|piece_type| piece_type != PieceType::Queen)
+            {
+                continue;
+            }
+            valid_moves[played.from as usize].push(played.to_id());
+            count += 1;
+        }
+        for (idx, valid_moves) in valid_moves.into_iter().enumerate() {
+            let cell = grid.get_cell_mut(&CellId::from_vec_idx(idx));
+            if cell
+                .item
+                .is_some_and(|piece| piece.side == self.position.turn)
+            {
+                cell.valid_moves = Some(valid_moves);
+            }
+        }
+        count
     }
 }
 
 #[derive(Debug)]
-pub struct Castle {
-    pub dest: CellId,
-    pub current: CellId,
-    pub linked_rook: CellId,
-    pub rook_move_direction: Direction,
+pub struct Promotion {
+    pub cell: CellId,
+    pub side: Side,
 }
-impl Castle {
-    pub fn is(&self, current_cell: &CellId, dest_cell: &CellId) -> bool {
-        (*current_cell == self.current) && (self.dest == *dest_cell)
+impl Promotion {
+    pub const CHOICES: [PieceType; 4] = [
+        PieceType::Queen,
+        PieceType::Rook,
+        PieceType::Bishop,
+        PieceType::Knight,
+    ];
+
+    pub fn is(piece: &Piece, dest_cell: &CellId) -> bool {
+        if piece.piece_type != PieceType::Pawn {
+            return false;
+        }
+        match piece.side {
+            Side::White => dest_cell.1 == 0,
+            Side::Black => dest_cell.1 == 7,
+        }
+    }
+    // picker squares run from the back rank towards the mover's side of the board
+    pub fn choices(&self) -> [(CellId, PieceType); 4] {
+        let direction = match self.side {
+            Side::White => Direction::Down,
+            Side::Black => Direction::Up,
+        };
+        let mut choices = [(self.cell, PieceType::Queen); 4];
+        for (n, piece_type) in Promotion::CHOICES.iter().enumerate() {
+            choices[n] = (
+                self.cell.try_next_cellid(direction, n as i32).unwrap(),
+                *piece_type,
+            );
+        }
+        choices
     }
-    pub fn execute(&self, grid: &mut Grid) {
-        let rook_new_id = self
-            .dest
-            .try_next_cellid(self.rook_move_direction, 1)
-            .unwrap();
-        let (current_, dest_) = grid.get_cell_mut_pair(&self.linked_rook, &rook_new_id);
-        let rook = current_.item.take().unwrap();
-        dest_.add_item(rook);
+    pub fn choice_at(&self, id: &CellId) -> Option<PieceType> {
+        self.choices()
+            .iter()
+            .find(|(cell, _)| cell == id)
+            .map(|(_, piece_type)| *piece_type)
     }
 }
@@ -0,0 +1,109 @@
+use chess::game::Game;
+use chess::grid::Grid;
+use macroquad::prelude::*;
+
+const ROW_HEIGHT: f32 = 80.0;
+const FONT_SIZE: f32 = 56.0;
+
+// the moves so far as numbered pairs inside `area`, the one that led to the
+// shown position lit; `first_ply` is the ply of the first move, odd when Black
+// moved first. Gives how many plies to show when a move is clicked
+pub fn move_list(
+    sans: &[&str],
+    first_ply: u32,
+    current: usize,
+    area: Rect,
+    mouse: Vec2,
+) -> Option<usize> {
+    draw_rectangle(
+        area.x,
+        area.y,
+        area.w,
+        area.h,
+        Color::new(0.12, 0.12, 0.12, 1.0),
+    );
+    // a move by black first gets a row of its own
+    let offset = (first_ply % 2) as usize;
+    let rows = (sans.len() + offset).div_ceil(2);
+    let visible = (area.h / ROW_HEIGHT) as usize;
+    // keep the lit move in view, as far down as it goes
+    let lit_row = (current + offset).saturating_sub(1) / 2;
+    let first_row = (lit_row + 1)
+        .saturating_sub(visible)
+        .min(rows.saturating_sub(visible));
+
+    let number_w = area.w * 0.25;
+    let san_w = (area.w - number_w) / 2.0;
+    let mut clicked = None;
+    for row in first_row..rows.min(first_row + visible) {
+        let y = area.y + (row - first_row) as f32 * ROW_HEIGHT;
+        let number = (first_ply as usize - offset) / 2 + row + 1;
+        draw_text(
+            format!("{}.", number).as_str(),
+            area.x + 16.0,
+            y + ROW_HEIGHT * 0.7,
+            FONT_SIZE,
+            GRAY,
+        );
+        for column in 0..2 {
+            let Some(index) = (row * 2 + column).checked_sub(offset) else {
+                continue;
+            };
+            let Some(san) = sans.get(index) else {
+                continue;
+            };
+            let cell = Rect::new(
+                area.x + number_w + column as f32 * san_w,
+                y,
+                san_w,
+                ROW_HEIGHT,
+            );
+            if index + 1 == current {
+                draw_rectangle(
+                    cell.x,
+                    cell.y,
+                    cell.w,
+                    cell.h,
+                    Color::new(0.2, 0.45, 0.6, 1.0),
+                );
+            }
+            draw_text(san, cell.x + 8.0, y + ROW_HEIGHT * 0.7, FONT_SIZE, WHITE);
+            if cell.contains(mouse) && is_mouse_button_pressed(MouseButton::Left) {
+                clicked = Some(index + 1);
+            }
+        }
+    }
+    clicked
+}
+
+// a past position of the live game, looked at but not played on
+pub struct View {
+    pub ply: usize,
+    pub grid: Grid,
+    pub game: Game,
+}
+
+impl View {
+    pub fn new(game: &Game, ply: usize) -> View {
+        let mut grid = Grid::new64();
+        let game = game.at_ply(&mut grid, ply);
+        View { ply, grid, game }
+    }
+}
+
+// steps through the live game's past with the arrow keys, `Home` and `End`;
+// gives the ply to show, the live game's own length meaning the game itself
+pub fn view_key_handler(current: usize, len: usize) -> Option<usize> {
+    let ply = if is_key_pressed(KeyCode::Right) {
+        (current + 1).min(len)
+    } else if is_key_pressed(KeyCode::Left) {
+        current.saturating_sub(1)
+    } else if is_key_pressed(KeyCode::Home) {
+        0
+    } else if is_key_pressed(KeyCode::End) || is_key_pressed(KeyCode::Escape) {
+        len
+    } else {
+        return None;
+    };
+    (ply != current).then_some(ply)
+}
@@ -0,0 +1,156 @@
+use crate::textures::PieceTxts;
+use chess::bitboard::{Position, PIECE_TYPES};
+use chess::game::{DrawReason, GameResult};
+use chess::pieces::{Piece, PieceType, Side};
+use macroquad::prelude::*;
+use std::time::Duration;
+
+fn draw_reason(reason: DrawReason) -> &'static str {
+    match reason {
+        DrawReason::TimeoutVsInsufficientMaterial => "time, no mating material",
+        DrawReason::InsufficientMaterial => "insufficient material",
+        DrawReason::FivefoldRepetition => "fivefold repetition",
+        DrawReason::SeventyFiveMoves => "75-move rule",
+        DrawReason::ThreefoldRepetition => "threefold repetition",
+        DrawReason::FiftyMoves => "50-move rule",
+    }
+}
+
+pub fn draw_result_banner(result: &GameResult, board: Rect) {
+    let message = match result {
+        GameResult::Checkmate { winner } => format!("Checkmate - {:?} wins", winner),
+        GameResult::Stalemate => "Stalemate - Draw".to_string(),
+        GameResult::Timeout { winner } => format!("Time - {:?} wins", winner),
+        GameResult::Draw(reason) => format!("Draw - {}", draw_reason(*reason)),
+    };
+    let font_size = (board.w / 14.0) as u16;
+    let dims = measure_text(&message, None, font_size, 1.0);
+    let banner_h = dims.height * 3.0;
+    let banner_y = board.y + (board.h - banner_h) / 2.0;
+    draw_rectangle(
+        board.x,
+        banner_y,
+        board.w,
+        banner_h,
+        Color::new(0.0, 0.0, 0.0, 0.75),
+    );
+    draw_text(
+        &message,
+        board.x + (board.w - dims.width) / 2.0,
+        banner_y + (banner_h + dims.offset_y) / 2.0,
+        font_size as f32,
+        WHITE,
+    );
+}
+
+// mm:ss, with tenths once under ten seconds
+fn clock_text(time: Duration) -> String {
+    if time < Duration::from_secs(10) {
+        return format!("0:{:04.1}", time.as_secs_f32());
+    }
+    let secs = time.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+// one side's clock, lit while it runs
+pub fn draw_clock(remaining: Duration, lit: bool, rect: Rect) {
+    let (fill, ink) = if lit {
+        (WHITE, BLACK)
+    } else {
+        (Color::new(0.15, 0.15, 0.15, 1.0), LIGHTGRAY)
+    };
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, fill);
+    let text = clock_text(remaining);
+    let font_size = (rect.h * 0.7) as u16;
+    let dims = measure_text(&text, None, font_size, 1.0);
+    draw_text(
+        &text,
+        rect.x + (rect.w - dims.width) / 2.0,
+        rect.y + (rect.h + dims.offset_y) / 2.0,
+        font_size as f32,
+        ink,
+    );
+}
+
+// small print in the side panel, a line per `\n`
+pub fn draw_note(text: &str, rect: Rect) {
+    let lines: Vec<&str> = text.lines().collect();
+    let line_h = rect.h / lines.len().max(2) as f32;
+    for (n, line) in lines.iter().enumerate() {
+        draw_text(
+            line,
+            rect.x,
+            rect.y + line_h * (n as f32 + 0.7),
+            line_h * 0.6,
+            LIGHTGRAY,
+        );
+    }
+}
+
+// pawn units, as material is usually counted
+fn points(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => 1,
+        PieceType::Knight | PieceType::Bishop => 3,
+        PieceType::Rook => 5,
+        PieceType::Queen => 9,
+        PieceType::King => 0,
+    }
+}
+
+// what `side` has on the board over the other side, in pawns
+pub fn material_lead(position: &Position, side: Side) -> i32 {
+    let material = |side| {
+        PIECE_TYPES
+            .into_iter()
+            .map(|piece_type| {
+                position.bitboard(side, piece_type).count_ones() as i32 * points(piece_type)
+            })
+            .sum::<i32>()
+    };
+    material(side) - material(side.switch())
+}
+
+// the pieces a side has taken, grouped by type from pawns up, then its lead if
+// it has one
+pub fn draw_tray(captured: &[Piece], lead: i32, piecetxts: &PieceTxts, rect: Rect) {
+    let size = rect.h;
+    let mut x = rect.x;
+    for piece_type in PIECE_TYPES {
+        let mut taken = captured
+            .iter()
+            .filter(|piece| piece.piece_type == piece_type)
+            .peekable();
+        if taken.peek().is_none() {
+            continue;
+        }
+        for piece in taken {
+            draw_texture_ex(
+                piecetxts.get(piece.piece_type, &piece.side),
+                x,
+                rect.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(size, size)),
+                    ..Default::default()
+                },
+            );
+            // pieces of a kind overlap
+            x += size * 0.4;
+        }
+        x += size * 0.7;
+    }
+    if lead > 0 {
+        draw_text(
+            format!("+{}", lead).as_str(),
+            x,
+            rect.y + size * 0.7,
+            size * 0.6,
+            LIGHTGRAY,
+        );
+    }
+}
+
+pub fn claim_hint(reason: DrawReason) -> String {
+    format!("D: claim {}", draw_reason(reason))
+}
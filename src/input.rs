@@ -1,62 +1,204 @@
-use crate::{game::Game, grid::CellId, grid::Grid};
+use crate::{render::BoardView, textures::PieceTxts};
+use chess::drawing::{Drawings, MarkColor};
+use chess::{game::Game, grid::CellId, grid::Grid, pieces::Side};
 use macroquad::prelude::*;
+// a piece picked up with the mouse, following it until it is let go
+pub struct Drag {
+    pub from: CellId,
+    // the piece was selected already, letting go of it where it stands deselects it
+    was_selected: bool,
+}
+
+// a press on one of the mover's pieces selects and lifts it; it moves by being
+// dropped on a valid square, or by a click there afterwards
 pub fn left_click_handler(
     grid: &mut Grid,
+    view: &BoardView,
     selected_cell: &mut Option<CellId>,
+    dragging: &mut Option<Drag>,
     game: &mut Game,
-    cam: &Camera2D,
     virtual_mouse: Vec2,
 ) {
-    let mouse_world = (virtual_mouse);
-    match selected_cell {
-        Some(selected) => {
-            if is_mouse_button_pressed(MouseButton::Left) {
-                let Some(dest) = grid.coord_to_cell_id(mouse_world.into()) else {
-                    return;
-                };
-                if dest == *selected {
-                    *selected_cell = None;
-                    return;
-                }
-                let (selected_c, dest_c) = grid.get_cell_mut_pair(selected, &dest);
-                selected_c
-                    .move_item_to(dest_c, game)
-                    .then(|| game.switch_turns(grid));
+    let mouse_world = virtual_mouse;
+    if let Some(drag) = dragging.take_if(|_| !is_mouse_button_down(MouseButton::Left)) {
+        drop_piece(grid, view, selected_cell, drag, game, mouse_world);
+        return;
+    }
+    if game.result.is_some() {
+        return;
+    }
+    if let Some(promotion) = &game.promotion {
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let Some(id) = view.coord_to_cell_id(mouse_world.into()) else {
+                return;
+            };
+            let Some(piece_type) = promotion.choice_at(&id) else {
+                return;
+            };
+            game.promote(grid, piece_type);
+        }
+        return;
+    }
+    if !is_mouse_button_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(cell) = view.coord_to_cell_id(mouse_world.into()) else {
+        return;
+    };
+    if grid
+        .get_cell(&cell)
+        .item
+        .as_ref()
+        .is_some_and(|piece| piece.side == game.turn())
+    {
+        *dragging = Some(Drag {
+            from: cell,
+            was_selected: *selected_cell == Some(cell),
+        });
+        *selected_cell = Some(cell);
+        return;
+    }
+    if let Some(selected) = selected_cell.take() {
+        game.play(grid, &selected, &cell);
+    }
+}
+
+// plays the lifted piece to the square under the mouse, or puts it back
+fn drop_piece(
+    grid: &mut Grid,
+    view: &BoardView,
+    selected_cell: &mut Option<CellId>,
+    drag: Drag,
+    game: &mut Game,
+    mouse_world: Vec2,
+) {
+    // the selection went elsewhere meanwhile, e.g. by a take back
+    if *selected_cell != Some(drag.from) {
+        return;
+    }
+    match view.coord_to_cell_id(mouse_world.into()) {
+        // let go where it was picked up, a plain click
+        Some(dest) if dest == drag.from => {
+            if drag.was_selected {
                 *selected_cell = None;
             }
         }
-        None => {
-            if is_mouse_button_pressed(MouseButton::Left) {
-                let Some(cell) = grid.coord_to_cell_id(mouse_world.into()) else {
-                    return;
-                };
+        Some(dest) => {
+            game.play(grid, &drag.from, &dest);
+            *selected_cell = None;
+        }
+        None => *selected_cell = None,
+    }
+}
 
-                let Some(piece) = &grid.get_cell(&cell).item else {
-                    return;
-                };
+// the piece being dragged, centred on the mouse
+pub fn on_drag(
+    grid: &Grid,
+    view: &BoardView,
+    selected_cell: &Option<CellId>,
+    dragging: &Option<Drag>,
+    piecetxts: &PieceTxts,
+    virtual_mouse: Vec2,
+    flip: bool,
+) {
+    let Some(drag) = dragging else {
+        return;
+    };
+    if *selected_cell != Some(drag.from) {
+        return;
+    }
+    let Some(piece) = &grid.get_cell(&drag.from).item else {
+        return;
+    };
+    view.draw_lifted(
+        piecetxts.get(piece.piece_type, &piece.side),
+        virtual_mouse,
+        flip,
+    );
+}
 
-                if piece.side != game.turn {
-                    return;
-                }
+pub fn on_promotion(view: &BoardView, game: &Game, piecetxts: &PieceTxts, flip: bool) {
+    let Some(promotion) = &game.promotion else {
+        return;
+    };
+    view.dim();
+    for (id, piece_type) in promotion.choices() {
+        view.draw_choice(&id, piecetxts.get(piece_type, &promotion.side), flip);
+    }
+}
 
-                *selected_cell = Some(cell);
-            }
+// against the computer a take back goes on to the player's own last move
+pub fn undo_redo_handler(
+    grid: &mut Grid,
+    selected_cell: &mut Option<CellId>,
+    game: &mut Game,
+    computer: Option<Side>,
+) {
+    let ctrl = is_key_down(KeyCode::LeftControl) | is_key_down(KeyCode::RightControl);
+    if !ctrl {
+        return;
+    }
+    let shift = is_key_down(KeyCode::LeftShift) | is_key_down(KeyCode::RightShift);
+    let step: fn(&mut Game, &mut Grid) -> bool = if is_key_pressed(KeyCode::Z) && !shift {
+        Game::undo
+    } else if is_key_pressed(KeyCode::Y) | (is_key_pressed(KeyCode::Z) && shift) {
+        Game::redo
+    } else {
+        return;
+    };
+    if step(game, grid) {
+        if Some(game.turn()) == computer {
+            step(game, grid);
         }
+        *selected_cell = None;
     }
 }
 
-pub fn on_selected(grid: &mut Grid, cell_id: &CellId, game: &mut Game) {
-    let cell = grid.get_cell(cell_id);
-    let Some(piece) = &cell.item else { return };
+// what colour a right click draws in, by the modifier keys held
+fn mark_color() -> MarkColor {
+    let shift = is_key_down(KeyCode::LeftShift) | is_key_down(KeyCode::RightShift);
+    let alt = is_key_down(KeyCode::LeftAlt) | is_key_down(KeyCode::RightAlt);
+    match (shift, alt) {
+        (true, true) => MarkColor::Yellow,
+        (true, false) => MarkColor::Red,
+        (false, true) => MarkColor::Blue,
+        (false, false) => MarkColor::Green,
+    }
+}
 
-    let Some(valid_moves) = &cell.valid_moves else {
-        let valid_moves_ = piece.calc_valid_moves(cell, grid, game);
-        grid.get_cell_mut(cell_id)
-            .add_valid_moves(valid_moves_, game);
+// a right drag draws an arrow, a right click circles the square; doing it again
+// in the same colour rubs it out. What is drawn also goes into `record`, the
+// game's drawings of the position, which a left click does not clear
+pub fn right_click_handler(
+    view: &BoardView,
+    drawings: &mut Drawings,
+    drawing_from: &mut Option<CellId>,
+    record: Option<&mut Drawings>,
+    virtual_mouse: Vec2,
+) {
+    let cell = view.coord_to_cell_id(virtual_mouse.into());
+    if is_mouse_button_pressed(MouseButton::Right) {
+        *drawing_from = cell;
+        return;
+    }
+    if !is_mouse_button_released(MouseButton::Right) {
+        return;
+    }
+    let (Some(from), Some(to)) = (drawing_from.take(), cell) else {
         return;
     };
-    for valid_move in valid_moves {
-        grid.get_cell(valid_move).highlight();
+    let color = mark_color();
+    if from == to {
+        let color = (drawings.circle(to) != Some(color)).then_some(color);
+        drawings.set_circle(to, color);
+        if let Some(record) = record {
+            record.set_circle(to, color);
+        }
+    } else {
+        let color = (drawings.arrow(from, to) != Some(color)).then_some(color);
+        drawings.set_arrow(from, to, color);
+        if let Some(record) = record {
+            record.set_arrow(from, to, color);
+        }
     }
-    cell.highlight();
 }
@@ -0,0 +1,16 @@
+pub mod ai;
+pub mod bitboard;
+pub mod clock;
+pub mod drawing;
+pub mod engine;
+pub mod fen;
+pub mod game;
+pub mod grid;
+pub mod path;
+pub mod perft;
+pub mod pgn;
+pub mod pieces;
+pub mod san;
+pub mod theme;
+pub mod uci;
+pub mod zobrist;
@@ -1,90 +1,306 @@
-use crate::game::{AlgebraicNotation, Game};
-use crate::grid::{CellId, Grid};
-use crate::input::{left_click_handler, on_selected};
-use crate::pieces::{Piece, PieceType, Side};
-use crate::textures::PieceTxts;
+use crate::history::{move_list, view_key_handler, View};
+use crate::hud::{claim_hint, draw_clock, draw_note, draw_result_banner, draw_tray, material_lead};
+use crate::input::{
+    left_click_handler, on_drag, on_promotion, right_click_handler, undo_redo_handler, Drag,
+};
+use crate::menu::{opponent_menu, settings_menu, time_control_menu, Setting};
+use crate::opponent::{Opponent, OpponentKind};
+use crate::orientation::{face, Orientation};
+use crate::render::{BoardView, Highlights};
+use crate::replay::Replay;
+use crate::storage::save_pgn;
+use crate::textures::{board_themes, piece_set_names, PieceTxts};
+use chess::clock::Clock;
+use chess::drawing::Drawings;
+use chess::game::Game;
+use chess::grid::{CellId, Grid};
+use chess::pieces::Side;
 use macroquad::prelude::*;
-mod game;
-mod grid;
+use std::time::Duration;
+mod history;
+mod hud;
 mod input;
-mod path;
-mod pieces;
+mod menu;
+mod opponent;
+mod orientation;
+mod render;
+mod replay;
+mod storage;
 mod textures;
 
 const VIRTUAL_WIDTH: f32 = 2048.0;
 const VIRTUAL_HEIGHT: f32 = 2048.0;
+// room right of the board for the clocks and moves, as a share of the board's width
+const PANEL_WIDTH: f32 = 0.3;
+const PANEL_VIRTUAL_WIDTH: f32 = VIRTUAL_WIDTH * PANEL_WIDTH;
+// where things go in the panel's render target
+const CLOCK_HEIGHT: f32 = 160.0;
+const TRAY_HEIGHT: f32 = 80.0;
+const MOVES_AREA: Rect = Rect {
+    x: 0.0,
+    y: 280.0,
+    w: PANEL_VIRTUAL_WIDTH,
+    h: VIRTUAL_HEIGHT - 700.0,
+};
+const NOTE_AREA: Rect = Rect {
+    x: 24.0,
+    y: VIRTUAL_HEIGHT - 400.0,
+    w: PANEL_VIRTUAL_WIDTH - 48.0,
+    h: 120.0,
+};
 
-#[macroquad::main("Chess")]
-async fn main() {
+// the screen shown over the board, if any
+#[derive(PartialEq, Clone, Copy)]
+enum Menu {
+    // picked before a game starts
+    TimeControl,
+    Opponent,
+    // piece set and board theme
+    Settings,
+}
+
+// `--engine <program>` names a UCI engine to play against, anything else a PGN file
+struct Args {
+    engine: Option<String>,
+    pgn: Option<String>,
+}
+
+impl Args {
+    fn parse() -> Args {
+        let mut args = Args {
+            engine: None,
+            pgn: None,
+        };
+        let mut words = std::env::args().skip(1);
+        while let Some(word) = words.next() {
+            match word.as_str() {
+                "--engine" => args.engine = words.next(),
+                _ => args.pgn = Some(word),
+            }
+        }
+        args
+    }
+
+    // what the menu calls the engine, the program's file name
+    fn engine_label(&self) -> Option<String> {
+        let engine = std::path::Path::new(self.engine.as_ref()?);
+        Some(engine.file_stem()?.to_string_lossy().into_owned())
+    }
+}
+
+fn main() {
+    macroquad::Window::new("Chess", game_loop());
+}
+
+async fn game_loop() {
+    // the side panel is a render target of its own, drawn right of the board
+    let panel_target = render_target(PANEL_VIRTUAL_WIDTH as u32, VIRTUAL_HEIGHT as u32);
+    panel_target.texture.set_filter(FilterMode::Linear);
     let render_target = render_target(VIRTUAL_WIDTH as u32, VIRTUAL_HEIGHT as u32);
     render_target.texture.set_filter(FilterMode::Linear);
 
     set_pc_assets_folder("assets");
-    let mut grid = Grid::new64(VIRTUAL_WIDTH / 8.0);
-    let box_txts: Box<PieceTxts> = Box::new(PieceTxts::default().await);
-    let piecetxts: &PieceTxts = Box::leak(box_txts);
+    let mut grid = Grid::new64();
+    let mut view = BoardView::new(VIRTUAL_WIDTH / 8.0);
+    let sets = piece_set_names();
+    let mut current_set = sets.iter().position(|set| set == "tatiana").unwrap_or(0);
+    let mut piecetxts = PieceTxts::load(&sets[current_set]).await.unwrap();
+    let themes = board_themes().await;
+    let mut current_theme = 0;
+    view.set_theme(&themes[current_theme]);
     build_textures_atlas();
 
-    let mut game = Game::new(&mut grid, piecetxts);
+    let args = Args::parse();
+    let mut game = Game::new(&mut grid);
+    // a PGN file given on the command line is opened for replay
+    let mut replay: Option<Replay> = None;
+    if let Some(path) = &args.pgn {
+        match Replay::load(path, &mut grid) {
+            Ok((replay_, game_)) => {
+                replay = Some(replay_);
+                game = game_;
+            }
+            Err(err) => println!("could not load {}: {}", path, err),
+        }
+    }
     let mut selected_cell: Option<CellId> = None;
+    let mut dragging: Option<Drag> = None;
+    let mut orientation = Orientation::Auto;
+    // arrows and circles on the board, until the next left click
+    let mut drawings = Drawings::default();
+    let mut drawing_from: Option<CellId> = None;
+    let mut computer: Option<Opponent> = None;
+    let mut menu = replay.is_none().then_some(Menu::TimeControl);
+    // a past position looked at from the move list
+    let mut viewing: Option<View> = None;
     let mut render_target_cam =
         Camera2D::from_display_rect(Rect::new(0., 0., VIRTUAL_WIDTH, VIRTUAL_HEIGHT));
     render_target_cam.render_target = Some(render_target.clone());
+    let mut panel_cam =
+        Camera2D::from_display_rect(Rect::new(0., 0., PANEL_VIRTUAL_WIDTH, VIRTUAL_HEIGHT));
+    panel_cam.render_target = Some(panel_target.clone());
     loop {
         // Get required scaling value
         let scale: f32 = f32::min(
-            screen_width() / VIRTUAL_WIDTH,
+            screen_width() / (VIRTUAL_WIDTH * (1.0 + PANEL_WIDTH)),
             screen_height() / VIRTUAL_HEIGHT,
         );
-        let flip: bool = match game.turn {
-            Side::White => false,
-            Side::Black => true,
+        let player = computer.as_ref().map(|opponent| opponent.side.switch());
+        let bottom = orientation.bottom(game.turn(), player, replay.is_some());
+        // Mouse position in the virtual screen
+        let mouse_on_board = Vec2 {
+            x: (mouse_position().0 - (screen_width() - (VIRTUAL_WIDTH * scale)) * 0.05) / scale,
+            y: (mouse_position().1 - (screen_height() - (VIRTUAL_HEIGHT * scale)) * 0.05) / scale,
         };
+        let (flip, virtual_mouse_pos) = face(
+            &mut render_target_cam,
+            bottom,
+            mouse_on_board,
+            vec2(VIRTUAL_WIDTH, VIRTUAL_HEIGHT),
+        );
 
-        // Mouse position in the virtual screen
-        let virtual_mouse_pos;
-        if !flip {
-            render_target_cam.rotation = 0.0;
-            virtual_mouse_pos = Vec2 {
-                x: (mouse_position().0 - (screen_width() - (VIRTUAL_WIDTH * scale)) * 0.05) / scale,
-                y: (mouse_position().1 - (screen_height() - (VIRTUAL_HEIGHT * scale)) * 0.05)
-                    / scale,
+        set_camera(&render_target_cam);
+
+        if is_key_pressed(KeyCode::S) {
+            save_pgn(&game);
+        }
+
+        if is_key_pressed(KeyCode::O) {
+            orientation = orientation.next();
+        }
+        if is_key_pressed(KeyCode::F) {
+            orientation = Orientation::flipped(bottom);
+        }
+        if is_key_pressed(KeyCode::C) {
+            view.coordinates = !view.coordinates;
+        }
+
+        // a new game's clock has to be picked before any other menu opens, and
+        // the settings wait for the opponent too
+        let setting_up = menu == Some(Menu::TimeControl);
+        let choosing = setting_up || (menu == Some(Menu::Opponent));
+        if !choosing && is_key_pressed(KeyCode::T) {
+            menu = match menu {
+                Some(Menu::Settings) => None,
+                _ => Some(Menu::Settings),
             };
-        } else {
-            render_target_cam.rotation = 180.0;
-            virtual_mouse_pos = Vec2 {
-                x: 2048.0
-                    - ((mouse_position().0 - (screen_width() - (VIRTUAL_WIDTH * scale)) * 0.05)
-                        / scale),
-                y: 2048.0
-                    - (mouse_position().1 - (screen_height() - (VIRTUAL_HEIGHT * scale)) * 0.05)
-                        / scale,
+        }
+        if replay.is_none() && !setting_up && is_key_pressed(KeyCode::M) {
+            menu = match menu {
+                None => Some(Menu::Opponent),
+                Some(_) => None,
             };
         }
 
-        set_camera(&render_target_cam);
+        // a new game starts from choosing its clock
+        if is_key_pressed(KeyCode::N) {
+            game = Game::new(&mut grid);
+            replay = None;
+            viewing = None;
+            selected_cell = None;
+            drawings.clear();
+            menu = Some(Menu::TimeControl);
+        }
 
-        left_click_handler(
-            &mut grid,
-            &mut selected_cell,
-            &mut game,
-            &render_target_cam,
-            virtual_mouse_pos,
-        );
+        if menu.is_none() {
+            // looking back, nothing is kept with the game
+            let record = viewing.is_none().then(|| game.drawings_mut());
+            right_click_handler(
+                &view,
+                &mut drawings,
+                &mut drawing_from,
+                record,
+                virtual_mouse_pos,
+            );
+            if is_mouse_button_pressed(MouseButton::Left) {
+                drawings.clear();
+            }
+        }
 
-        grid.draw(flip);
+        match &mut replay {
+            Some(replay) => replay.key_handler(&mut grid, &mut game),
+            None if menu.is_some() => {}
+            None => {
+                game.tick(Duration::from_secs_f32(get_frame_time()));
+                let computer_side = computer.as_ref().map(|opponent| opponent.side);
+                let len = game.history.len();
+                let current = viewing.as_ref().map_or(len, |view| view.ply);
+                if let Some(ply) = view_key_handler(current, len) {
+                    viewing = (ply < len).then(|| View::new(&game, ply));
+                    selected_cell = None;
+                }
+                // the game goes on from the viewed position, the moves after it
+                // can be played again with redo
+                if viewing.is_some() && is_key_pressed(KeyCode::Enter) {
+                    let ply = viewing.take().unwrap().ply;
+                    while game.history.len() > ply {
+                        game.undo(&mut grid);
+                    }
+                }
+                // a past position is only looked at
+                if viewing.is_none() {
+                    if (computer_side != Some(game.turn())) && is_key_pressed(KeyCode::D) {
+                        game.claim_draw();
+                    }
+                    undo_redo_handler(&mut grid, &mut selected_cell, &mut game, computer_side);
+                    if computer_side != Some(game.turn()) {
+                        left_click_handler(
+                            &mut grid,
+                            &view,
+                            &mut selected_cell,
+                            &mut dragging,
+                            &mut game,
+                            virtual_mouse_pos,
+                        );
+                    }
+                }
+                if let Some(opponent) = &mut computer {
+                    if let Err(err) = opponent.update(&mut grid, &mut game) {
+                        println!("opponent gave up: {}", err);
+                        computer = None;
+                    }
+                }
+            }
+        }
 
-        if let Some(cell) = &selected_cell {
-            on_selected(&mut grid, cell, &mut game);
+        match &viewing {
+            Some(viewed) => {
+                let highlights = Highlights::of(&viewed.grid, &viewed.game, None, &drawings);
+                view.draw(&viewed.grid, &piecetxts, flip, None, &highlights);
+            }
+            None => {
+                let lifted = dragging
+                    .as_ref()
+                    .map(|drag| &drag.from)
+                    .filter(|from| selected_cell.as_ref() == Some(*from));
+                let highlights = Highlights::of(&grid, &game, selected_cell, &drawings);
+                view.draw(&grid, &piecetxts, flip, lifted, &highlights);
+                on_promotion(&view, &game, &piecetxts, flip);
+                on_drag(
+                    &grid,
+                    &view,
+                    &selected_cell,
+                    &dragging,
+                    &piecetxts,
+                    virtual_mouse_pos,
+                    flip,
+                );
+            }
         }
         set_default_camera();
-        draw_texture_ex(
-            &render_target.texture,
+        let board = Rect::new(
             (screen_width() - (VIRTUAL_WIDTH * scale)) * 0.05,
             (screen_height() - (VIRTUAL_HEIGHT * scale)) * 0.05,
+            VIRTUAL_WIDTH * scale,
+            VIRTUAL_HEIGHT * scale,
+        );
+        draw_texture_ex(
+            &render_target.texture,
+            board.x,
+            board.y,
             WHITE,
             DrawTextureParams {
-                dest_size: Some(vec2(VIRTUAL_WIDTH * scale, VIRTUAL_HEIGHT * scale)),
+                dest_size: Some(vec2(board.w, board.h)),
                 source: None,
 
                 rotation: 0.0,
@@ -93,6 +309,153 @@ async fn main() {
                 pivot: None,
             },
         );
+        if let Some(result) = &game.result {
+            if viewing.is_none() {
+                draw_result_banner(result, board);
+            }
+        }
+
+        let panel = Rect::new(
+            board.x + board.w,
+            board.y,
+            PANEL_VIRTUAL_WIDTH * scale,
+            board.h,
+        );
+        let panel_mouse = (Vec2::from(mouse_position()) - panel.point()) / scale;
+        set_camera(&panel_cam);
+        clear_background(Color::new(0.08, 0.08, 0.08, 1.0));
+        let clicked = match &replay {
+            Some(replay) => move_list(
+                &replay.sans(),
+                replay.first_ply,
+                replay.ply,
+                MOVES_AREA,
+                panel_mouse,
+            ),
+            None => {
+                let sans: Vec<&str> = game
+                    .history
+                    .iter()
+                    .map(|played| played.san.as_str())
+                    .collect();
+                let first_ply = game
+                    .history
+                    .first()
+                    .map_or(game.move_count, |played| played.ply);
+                let current = viewing.as_ref().map_or(sans.len(), |view| view.ply);
+                move_list(&sans, first_ply, current, MOVES_AREA, panel_mouse)
+            }
+        };
+        // each side's clock and the pieces it took, on its own end of the board
+        let (top, bottom) = if flip {
+            (Side::White, Side::Black)
+        } else {
+            (Side::Black, Side::White)
+        };
+        let shown = viewing.as_ref().map_or(&game, |viewed| &viewed.game);
+        for (side, y) in [
+            (top, CLOCK_HEIGHT + 10.0),
+            (bottom, VIRTUAL_HEIGHT - CLOCK_HEIGHT - TRAY_HEIGHT - 10.0),
+        ] {
+            let captured = match side {
+                Side::White => &shown.black_stack,
+                Side::Black => &shown.white_stack,
+            };
+            let lead = material_lead(&shown.position, side);
+            let rect = Rect::new(32.0, y, PANEL_VIRTUAL_WIDTH - 64.0, TRAY_HEIGHT);
+            draw_tray(captured, lead, &piecetxts, rect);
+        }
+        if let Some(clock) = &game.clock {
+            let running = replay.is_none() && menu.is_none() && game.result.is_none();
+            let w = PANEL_VIRTUAL_WIDTH - 64.0;
+            for (side, y) in [(top, 0.0), (bottom, VIRTUAL_HEIGHT - CLOCK_HEIGHT)] {
+                let rect = Rect::new(32.0, y, w, CLOCK_HEIGHT);
+                draw_clock(
+                    clock.remaining(side),
+                    running && (side == game.turn()),
+                    rect,
+                );
+            }
+        }
+        if let Some(viewed) = &viewing {
+            let note = format!(
+                "ply {}: Enter plays on from here\nEsc goes back to the game",
+                viewed.ply
+            );
+            draw_note(&note, NOTE_AREA);
+        } else if let Some(reason) = game.claimable_draw() {
+            if replay.is_none()
+                && computer
+                    .as_ref()
+                    .is_none_or(|opponent| opponent.side != game.turn())
+            {
+                draw_note(&claim_hint(reason), NOTE_AREA);
+            }
+        }
+        set_default_camera();
+        draw_texture_ex(
+            &panel_target.texture,
+            panel.x,
+            panel.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(panel.w, panel.h)),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+        if let (Some(ply), None) = (clicked, menu) {
+            match &mut replay {
+                Some(replay) => replay.jump(&mut grid, &mut game, ply),
+                None => {
+                    viewing = (ply < game.history.len()).then(|| View::new(&game, ply));
+                    selected_cell = None;
+                }
+            }
+        }
+
+        if menu == Some(Menu::TimeControl) {
+            let current = game.clock.as_ref().map(|clock| clock.control);
+            if let Some(choice) = time_control_menu(board, current) {
+                game.clock = choice.map(Clock::new);
+                menu = Some(Menu::Opponent);
+            }
+        } else if menu == Some(Menu::Opponent) {
+            let current = computer
+                .as_ref()
+                .map(|opponent| (opponent.kind, opponent.side));
+            if let Some(choice) = opponent_menu(board, current, args.engine_label().as_deref()) {
+                if choice != current {
+                    computer = match choice {
+                        None => None,
+                        Some((OpponentKind::Computer, side)) => Some(Opponent::computer(side)),
+                        Some((OpponentKind::Engine, side)) => {
+                            let program = args.engine.as_deref().unwrap();
+                            Opponent::engine(side, program)
+                                .inspect_err(|err| println!("could not start {}: {}", program, err))
+                                .ok()
+                        }
+                    };
+                }
+                selected_cell = None;
+                menu = None;
+            }
+        } else if menu == Some(Menu::Settings) {
+            match settings_menu(board, &sets, current_set, &themes, current_theme) {
+                Some(Setting::Pieces(n)) => match PieceTxts::load(&sets[n]).await {
+                    Ok(txts) => {
+                        piecetxts = txts;
+                        current_set = n;
+                    }
+                    Err(err) => println!("could not load the {} pieces: {}", sets[n], err),
+                },
+                Some(Setting::Board(n)) => {
+                    view.set_theme(&themes[n]);
+                    current_theme = n;
+                }
+                None => {}
+            }
+        }
         next_frame().await
     }
 }
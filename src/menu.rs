@@ -0,0 +1,174 @@
+use crate::opponent::OpponentKind;
+use chess::clock::{Increment, TimeControl};
+use chess::pieces::Side;
+use chess::theme::BoardTheme;
+use macroquad::prelude::*;
+
+// a labelled box in screen space, true when clicked this frame
+fn button(label: &str, rect: Rect, selected: bool) -> bool {
+    let hovered = rect.contains(mouse_position().into());
+    let fill = if selected {
+        Color::new(0.2, 0.45, 0.6, 0.95)
+    } else if hovered {
+        Color::new(0.3, 0.3, 0.3, 0.95)
+    } else {
+        Color::new(0.15, 0.15, 0.15, 0.95)
+    };
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, fill);
+    let font_size = (rect.h * 0.5) as u16;
+    let dims = measure_text(label, None, font_size, 1.0);
+    draw_text(
+        label,
+        rect.x + (rect.w - dims.width) / 2.0,
+        rect.y + (rect.h + dims.offset_y) / 2.0,
+        font_size as f32,
+        WHITE,
+    );
+    hovered && is_mouse_button_pressed(MouseButton::Left)
+}
+
+// who the computer, or the engine if one was given, plays; drawn over the board
+// and gives the pick once one is made
+pub fn opponent_menu(
+    board: Rect,
+    current: Option<(OpponentKind, Side)>,
+    engine: Option<&str>,
+) -> Option<Option<(OpponentKind, Side)>> {
+    overlay(board, "Play against");
+
+    let mut choices = vec![
+        ("Two players".to_string(), None),
+        (
+            "Computer plays Black".to_string(),
+            Some((OpponentKind::Computer, Side::Black)),
+        ),
+        (
+            "Computer plays White".to_string(),
+            Some((OpponentKind::Computer, Side::White)),
+        ),
+    ];
+    if let Some(engine) = engine {
+        for side in [Side::Black, Side::White] {
+            choices.push((
+                format!("{} plays {:?}", engine, side),
+                Some((OpponentKind::Engine, side)),
+            ));
+        }
+    }
+    pick(board, choices, current)
+}
+
+// what was picked in the settings menu
+pub enum Setting {
+    Pieces(usize),
+    Board(usize),
+}
+
+// the piece sets and the board themes side by side, each by its index
+pub fn settings_menu(
+    board: Rect,
+    sets: &[String],
+    current_set: usize,
+    themes: &[BoardTheme],
+    current_theme: usize,
+) -> Option<Setting> {
+    overlay(board, "Settings");
+    let w = board.w * 0.4;
+    let gap = board.w * 0.06;
+    let areas = [
+        Rect::new(board.x + gap, board.y, w, board.h),
+        Rect::new(board.x + board.w - gap - w, board.y, w, board.h),
+    ];
+    for (heading, area) in ["Pieces", "Board"].into_iter().zip(areas) {
+        let font_size = (board.w / 24.0) as u16;
+        let dims = measure_text(heading, None, font_size, 1.0);
+        draw_text(
+            heading,
+            area.x + (area.w - dims.width) / 2.0,
+            board.y + board.h * 0.36,
+            font_size as f32,
+            LIGHTGRAY,
+        );
+    }
+    let sets = sets.iter().cloned().zip(0..).collect();
+    let themes = themes
+        .iter()
+        .map(|theme| theme.name.clone())
+        .zip(0..)
+        .collect();
+    let set = column(board, areas[0], sets, current_set).map(Setting::Pieces);
+    let theme = column(board, areas[1], themes, current_theme).map(Setting::Board);
+    set.or(theme)
+}
+
+// the time controls on offer before a game
+const TIME_CONTROLS: [(u64, u64, Increment); 6] = [
+    (1, 0, Increment::Fischer),
+    (3, 2, Increment::Fischer),
+    (5, 3, Increment::Bronstein),
+    (10, 5, Increment::Delay),
+    (15, 10, Increment::Fischer),
+    (30, 0, Increment::Fischer),
+];
+
+// the clock for the next game, picked before it starts
+pub fn time_control_menu(board: Rect, current: Option<TimeControl>) -> Option<Option<TimeControl>> {
+    overlay(board, "Time control");
+    let mut choices = vec![("No clock".to_string(), None)];
+    for (base, increment, kind) in TIME_CONTROLS {
+        let control = TimeControl::new(base, increment, kind);
+        choices.push((control.label(), Some(control)));
+    }
+    pick(board, choices, current)
+}
+
+// dims the board and puts the title over it
+fn overlay(board: Rect, title: &str) {
+    draw_rectangle(
+        board.x,
+        board.y,
+        board.w,
+        board.h,
+        Color::new(0.0, 0.0, 0.0, 0.6),
+    );
+    let font_size = (board.w / 14.0) as u16;
+    let dims = measure_text(title, None, font_size, 1.0);
+    draw_text(
+        title,
+        board.x + (board.w - dims.width) / 2.0,
+        board.y + board.h * 0.3,
+        font_size as f32,
+        WHITE,
+    );
+}
+
+// a column of buttons under the title, the choice of the clicked one
+fn pick<T: PartialEq>(board: Rect, choices: Vec<(String, T)>, current: T) -> Option<T> {
+    let w = board.w * 0.6;
+    let area = Rect::new(board.x + (board.w - w) / 2.0, board.y, w, board.h);
+    column(board, area, choices, current)
+}
+
+// buttons down `area` from under the title, sized to the board
+fn column<T: PartialEq>(
+    board: Rect,
+    area: Rect,
+    choices: Vec<(String, T)>,
+    current: T,
+) -> Option<T> {
+    // rows shrink to keep a long list on the board
+    let h = board.h * f32::min(0.08, 0.55 / (choices.len() as f32 * 1.3));
+    let mut picked = None;
+    for (n, (label, choice)) in choices.into_iter().enumerate() {
+        let rect = Rect::new(
+            area.x,
+            board.y + board.h * 0.38 + n as f32 * h * 1.3,
+            area.w,
+            h,
+        );
+        if button(&label, rect, choice == current) {
+            picked = Some(choice);
+        }
+    }
+    picked
+}
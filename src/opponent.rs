@@ -0,0 +1,181 @@
+use chess::ai::{search, Limits};
+use chess::bitboard::{BitMove, Position};
+use chess::engine::{Engine, Reply};
+use chess::game::Game;
+use chess::grid::Grid;
+use chess::pieces::Side;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::time::Duration;
+
+const MOVE_TIME: Duration = Duration::from_secs(1);
+// moves the clock is shared out over, so a short game is not lost on time
+const MOVES_TO_GO: u32 = 30;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum OpponentKind {
+    Computer,
+    // the UCI engine given with --engine
+    Engine,
+}
+
+enum Brain {
+    // the built-in search, thinking on a thread of its own so frames keep coming
+    Builtin {
+        answer: Option<Receiver<Option<BitMove>>>,
+        stop: Arc<AtomicBool>,
+    },
+    Engine(Engine),
+}
+
+pub struct Opponent {
+    pub side: Side,
+    pub kind: OpponentKind,
+    brain: Brain,
+    // the position the awaited answer is for
+    asked: Option<Position>,
+}
+
+impl Opponent {
+    pub fn computer(side: Side) -> Opponent {
+        Opponent {
+            side,
+            kind: OpponentKind::Computer,
+            brain: Brain::Builtin {
+                answer: None,
+                stop: Arc::new(AtomicBool::new(false)),
+            },
+            asked: None,
+        }
+    }
+
+    pub fn engine(side: Side, program: &str) -> Result<Opponent, String> {
+        let engine = Engine::spawn(program, &[]).map_err(|err| err.to_string())?;
+        Ok(Opponent {
+            side,
+            kind: OpponentKind::Engine,
+            brain: Brain::Engine(engine),
+            asked: None,
+        })
+    }
+
+    // asks for a move on the opponent's turn and plays the answer once it is in,
+    // the same way a click does
+    pub fn update(&mut self, grid: &mut Grid, game: &mut Game) -> Result<(), String> {
+        // an engine still starting up has nothing to say yet
+        if let Brain::Engine(engine) = &mut self.brain {
+            if !engine.is_ready() {
+                engine.poll().map_err(|err| err.to_string())?;
+                if !engine.is_ready() {
+                    return Ok(());
+                }
+            }
+        }
+        if (game.turn() != self.side) || game.result.is_some() || game.promotion.is_some() {
+            return self.cancel();
+        }
+        // the board changed under the search, e.g. by undo and redo
+        if self.asked.is_some_and(|asked| asked != game.position) {
+            self.cancel()?;
+        }
+        if self.asked.is_none() {
+            self.asked = Some(game.position);
+            return self.ask(game);
+        }
+        let answer = match &mut self.brain {
+            Brain::Builtin { answer, .. } => match answer.as_ref().unwrap().try_recv() {
+                Ok(best) => {
+                    *answer = None;
+                    best.map(|best| (best.from_id(), best.to_id(), best.promotion))
+                }
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(TryRecvError::Disconnected) => return Err("search failed".to_string()),
+            },
+            Brain::Engine(engine) => match engine.poll().map_err(|err| err.to_string())? {
+                Reply::Starting | Reply::Ready | Reply::Thinking => return Ok(()),
+                Reply::BestMove(from, to, promotion) => Some((from, to, promotion)),
+                Reply::NoMove => None,
+            },
+        };
+        self.asked = None;
+        let Some((from, to, promotion)) = answer else {
+            return Err("no move to play".to_string());
+        };
+        if !game.make_move(grid, &from, &to, promotion) {
+            return Err(format!(
+                "illegal move {}",
+                chess::san::coordinate(&from, &to, promotion)
+            ));
+        }
+        Ok(())
+    }
+
+    fn ask(&mut self, game: &Game) -> Result<(), String> {
+        let movetime = match &game.clock {
+            Some(clock) => MOVE_TIME.min(clock.remaining(self.side) / MOVES_TO_GO),
+            None => MOVE_TIME,
+        };
+        match &mut self.brain {
+            Brain::Builtin { answer, stop } => {
+                *answer = Some(think(game.position, movetime, stop.clone()));
+                Ok(())
+            }
+            Brain::Engine(engine) => engine.go(game, movetime).map_err(|err| err.to_string()),
+        }
+    }
+
+    fn cancel(&mut self) -> Result<(), String> {
+        if self.asked.take().is_none() {
+            return Ok(());
+        }
+        match &mut self.brain {
+            Brain::Builtin { answer, stop } => {
+                *answer = None;
+                stop.store(true, Ordering::Relaxed);
+                *stop = Arc::new(AtomicBool::new(false));
+                Ok(())
+            }
+            Brain::Engine(engine) => engine.stop().map_err(|err| err.to_string()),
+        }
+    }
+}
+
+impl Drop for Opponent {
+    fn drop(&mut self) {
+        let _ = self.cancel();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn think(
+    position: Position,
+    movetime: Duration,
+    stop: Arc<AtomicBool>,
+) -> Receiver<Option<BitMove>> {
+    let (sender, receiver) = channel();
+    std::thread::spawn(move || {
+        let limits = Limits {
+            depth: None,
+            movetime: Some(movetime),
+        };
+        let _ = sender.send(search(&position, &limits, &stop, |_| {}).best);
+    });
+    receiver
+}
+
+// no threads (nor clock) in the browser, a shallow search on the spot has to do
+#[cfg(target_arch = "wasm32")]
+fn think(
+    position: Position,
+    _movetime: Duration,
+    stop: Arc<AtomicBool>,
+) -> Receiver<Option<BitMove>> {
+    let (sender, receiver) = channel();
+    let limits = Limits {
+        depth: Some(3),
+        movetime: None,
+    };
+    let _ = sender.send(search(&position, &limits, &stop, |_| {}).best);
+    receiver
+}
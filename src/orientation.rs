@@ -0,0 +1,53 @@
+use chess::pieces::Side;
+use macroquad::prelude::*;
+
+// which way up the board is drawn
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Orientation {
+    // turns to the side to move in a two player game, stays with the player
+    // against an opponent and with White when going through a saved game
+    Auto,
+    White,
+    Black,
+}
+
+impl Orientation {
+    // the order `O` goes through them in
+    pub fn next(self) -> Orientation {
+        match self {
+            Orientation::Auto => Orientation::White,
+            Orientation::White => Orientation::Black,
+            Orientation::Black => Orientation::Auto,
+        }
+    }
+
+    // fixed the other way up from how the board is shown now
+    pub fn flipped(bottom: Side) -> Orientation {
+        match bottom {
+            Side::White => Orientation::Black,
+            Side::Black => Orientation::White,
+        }
+    }
+
+    // the side at the bottom of the board; `player` is the human's side when
+    // there is an opponent
+    pub fn bottom(self, turn: Side, player: Option<Side>, replaying: bool) -> Side {
+        match (self, player) {
+            (Orientation::White, _) => Side::White,
+            (Orientation::Black, _) => Side::Black,
+            (Orientation::Auto, Some(side)) => side,
+            (Orientation::Auto, None) if replaying => Side::White,
+            (Orientation::Auto, None) => turn,
+        }
+    }
+}
+
+// turns the board camera for Black at the bottom and maps the mouse, given
+// relative to the board's top left corner, into the board's render target;
+// pieces are drawn with the same flip so they stay upright
+pub fn face(camera: &mut Camera2D, bottom: Side, mouse: Vec2, size: Vec2) -> (bool, Vec2) {
+    let flip = bottom == Side::Black;
+    camera.rotation = if flip { 180.0 } else { 0.0 };
+    let mouse = if flip { size - mouse } else { mouse };
+    (flip, mouse)
+}
@@ -0,0 +1,72 @@
+use crate::bitboard::Position;
+use crate::game::{Game, GameResult};
+use crate::grid::Grid;
+use crate::san;
+
+// number of leaf nodes of the move tree `depth` plies deep, for checking the move generator
+pub fn perft(grid: &mut Grid, game: &mut Game, depth: u32) -> u64 {
+    // draws by rule end a game, but not the move tree; the caller's game gets
+    // its draw back once counted
+    let drawn = game
+        .result
+        .take_if(|result| matches!(result, GameResult::Draw(_)));
+    let nodes = count(grid, game, depth);
+    if drawn.is_some() {
+        game.result = drawn;
+    }
+    nodes
+}
+
+fn count(grid: &mut Grid, game: &mut Game, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = game.all_valid_moves();
+    // the valid moves are already known one ply up, no need to play them out
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    let mut nodes = 0;
+    for (from, to, promotion) in moves {
+        game.make_move(grid, &from, &to, promotion);
+        nodes += perft(grid, game, depth - 1);
+        game.undo(grid);
+    }
+    nodes
+}
+
+// perft split by root move, in coordinate notation
+pub fn divide(grid: &mut Grid, game: &mut Game, depth: u32) -> Vec<(String, u64)> {
+    let mut split = Vec::new();
+    if depth == 0 {
+        return split;
+    }
+    let drawn = game
+        .result
+        .take_if(|result| matches!(result, GameResult::Draw(_)));
+    for (from, to, promotion) in game.all_valid_moves() {
+        game.make_move(grid, &from, &to, promotion);
+        let nodes = perft(grid, game, depth - 1);
+        game.undo(grid);
+        split.push((san::coordinate(&from, &to, promotion), nodes));
+    }
+    if drawn.is_some() {
+        game.result = drawn;
+    }
+    split
+}
+
+// perft straight on the bitboards, without the grid and game bookkeeping
+pub fn perft_position(position: &Position, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = position.legal_moves();
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    moves
+        .iter()
+        .map(|played| perft_position(&position.make(played), depth - 1))
+        .sum()
+}
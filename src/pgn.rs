@@ -0,0 +1,396 @@
+use std::fmt;
+
+use crate::fen::FenError;
+use crate::game::{Game, GameResult, STARTING_FEN};
+use crate::grid::Grid;
+use crate::pieces::Side;
+use crate::san;
+
+// the seven tag roster, Result is taken from the game itself
+pub struct PgnTags {
+    pub event: String,
+    pub site: String,
+    pub date: String,
+    pub round: String,
+    pub white: String,
+    pub black: String,
+}
+
+impl Default for PgnTags {
+    fn default() -> PgnTags {
+        PgnTags {
+            event: "?".to_string(),
+            site: "?".to_string(),
+            date: "????.??.??".to_string(),
+            round: "?".to_string(),
+            white: "?".to_string(),
+            black: "?".to_string(),
+        }
+    }
+}
+
+pub fn result_token(result: &Option<GameResult>) -> &'static str {
+    match result.as_ref().map(|result| result.winner()) {
+        Some(Some(Side::White)) => "1-0",
+        Some(Some(Side::Black)) => "0-1",
+        Some(None) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+const LINE_WIDTH: usize = 80;
+
+impl Game {
+    pub fn to_pgn(&self, tags: &PgnTags) -> String {
+        let result = result_token(&self.result);
+        let mut pgn = String::new();
+        for (name, value) in [
+            ("Event", &tags.event),
+            ("Site", &tags.site),
+            ("Date", &tags.date),
+            ("Round", &tags.round),
+            ("White", &tags.white),
+            ("Black", &tags.black),
+        ] {
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, escape(value)));
+        }
+        pgn.push_str(&format!("[Result \"{}\"]\n", result));
+        if self.start_fen != STARTING_FEN {
+            pgn.push_str("[SetUp \"1\"]\n");
+            pgn.push_str(&format!("[FEN \"{}\"]\n", self.start_fen));
+        }
+        pgn.push('\n');
+
+        let mut tokens: Vec<String> = Vec::new();
+        // drawings on the board go in as comment commands after the move they follow
+        if !self.start_drawings.is_empty() {
+            tokens.push(format!("{{{}}}", self.start_drawings.to_commands()));
+        }
+        for (i, played) in self.history.iter().enumerate() {
+            let number = played.ply / 2 + 1;
+            // Black's move is numbered again after a comment breaks up the pair
+            let after_comment = (i > 0) && !self.history[i - 1].drawings.is_empty();
+            if played.ply % 2 == 0 {
+                tokens.push(format!("{}.", number));
+            } else if (i == 0) || after_comment {
+                tokens.push(format!("{}...", number));
+            }
+            tokens.push(played.san.clone());
+            if !played.drawings.is_empty() {
+                tokens.push(format!("{{{}}}", played.drawings.to_commands()));
+            }
+        }
+        tokens.push(result.to_string());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && (line.len() + 1 + token.len() > LINE_WIDTH) {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        pgn.push_str(&line);
+        pgn.push('\n');
+        pgn
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PgnError {
+    UnterminatedTag,
+    UnterminatedComment,
+    UnbalancedVariation,
+    NoGame,
+    Fen(FenError),
+    IllegalMove { index: usize, san: String },
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PgnError::UnterminatedTag => write!(f, "tag pair is missing its closing ']'"),
+            PgnError::UnterminatedComment => write!(f, "comment is missing its closing '}}'"),
+            PgnError::UnbalancedVariation => write!(f, "unbalanced parentheses in variations"),
+            PgnError::NoGame => write!(f, "no game found"),
+            PgnError::Fen(err) => write!(f, "bad FEN tag: {}", err),
+            PgnError::IllegalMove { index, san } => {
+                write!(f, "ply {} '{}' is not legal here", index + 1, san)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+#[derive(Debug, Clone, Default)]
+pub struct PgnMove {
+    pub san: String,
+    pub nags: Vec<u8>,
+    pub comments: Vec<String>,
+    // alternatives to this move, each a line of its own
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<PgnMove>,
+    pub result: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Open,
+    Close,
+    Nag(u8),
+    Move(String),
+    Result(String),
+}
+
+fn suffix_nag(suffix: &str) -> Option<u8> {
+    match suffix {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None,
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, PgnError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line_start = true;
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line_start = true;
+            continue;
+        }
+        let at_line_start = line_start;
+        line_start = false;
+        match c {
+            _ if c.is_whitespace() => {}
+            // escape mechanism, the rest of the line is ignored
+            '%' if at_line_start => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line_start = true;
+                        break;
+                    }
+                }
+            }
+            '[' => {
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace() && (*c != '"')) {
+                    name.push(c);
+                }
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if chars.next() != Some('"') {
+                    return Err(PgnError::UnterminatedTag);
+                }
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => value.push(chars.next().ok_or(PgnError::UnterminatedTag)?),
+                        Some('"') => break,
+                        Some(c) => value.push(c),
+                        None => return Err(PgnError::UnterminatedTag),
+                    }
+                }
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if chars.next() != Some(']') {
+                    return Err(PgnError::UnterminatedTag);
+                }
+                tokens.push(Token::Tag(name, value));
+            }
+            '{' => {
+                let mut comment = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => comment.push(c),
+                        None => return Err(PgnError::UnterminatedComment),
+                    }
+                }
+                tokens.push(Token::Comment(comment.trim().to_string()));
+            }
+            ';' => {
+                let mut comment = String::new();
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line_start = true;
+                        break;
+                    }
+                    comment.push(c);
+                }
+                tokens.push(Token::Comment(comment.trim().to_string()));
+            }
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '$' => {
+                let mut digits = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+                    digits.push(c);
+                }
+                if let Ok(nag) = digits.parse() {
+                    tokens.push(Token::Nag(nag));
+                }
+            }
+            _ => {
+                let mut symbol = String::from(c);
+                while let Some(c) =
+                    chars.next_if(|c| !c.is_whitespace() && !"{}()[];$".contains(*c))
+                {
+                    symbol.push(c);
+                }
+                push_symbol(&mut tokens, &symbol);
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn push_symbol(tokens: &mut Vec<Token>, symbol: &str) {
+    if ["1-0", "0-1", "1/2-1/2", "*"].contains(&symbol) {
+        tokens.push(Token::Result(symbol.to_string()));
+        return;
+    }
+    // move numbers, possibly glued to the move as in "12.e4" or "12...e5"
+    let mut symbol = symbol;
+    if symbol.starts_with(|c: char| c.is_ascii_digit()) && symbol.contains('.') {
+        symbol = symbol.trim_start_matches(|c: char| c.is_ascii_digit() || (c == '.'));
+    }
+    if symbol.is_empty() {
+        return;
+    }
+    let san = symbol.trim_end_matches(['!', '?']);
+    let suffix = &symbol[san.len()..];
+    if !san.is_empty() {
+        tokens.push(Token::Move(san.to_string()));
+    }
+    if let Some(nag) = suffix_nag(suffix) {
+        tokens.push(Token::Nag(nag));
+    }
+}
+
+// reads moves until the end of the current line of play
+fn parse_line(
+    tokens: &[Token],
+    idx: &mut usize,
+    depth: u32,
+) -> Result<(Vec<PgnMove>, Option<String>), PgnError> {
+    let mut moves: Vec<PgnMove> = Vec::new();
+    let mut pending_comments: Vec<String> = Vec::new();
+    while let Some(token) = tokens.get(*idx) {
+        match token {
+            Token::Tag(_, _) => break,
+            Token::Result(result) => {
+                *idx += 1;
+                if depth > 0 {
+                    continue;
+                }
+                return Ok((moves, Some(result.clone())));
+            }
+            Token::Move(san) => {
+                moves.push(PgnMove {
+                    san: san.clone(),
+                    comments: std::mem::take(&mut pending_comments),
+                    ..Default::default()
+                });
+            }
+            Token::Nag(nag) => {
+                if let Some(last) = moves.last_mut() {
+                    last.nags.push(*nag);
+                }
+            }
+            Token::Comment(comment) => match moves.last_mut() {
+                Some(last) => last.comments.push(comment.clone()),
+                None => pending_comments.push(comment.clone()),
+            },
+            Token::Open => {
+                *idx += 1;
+                let (variation, _) = parse_line(tokens, idx, depth + 1)?;
+                if tokens.get(*idx) != Some(&Token::Close) {
+                    return Err(PgnError::UnbalancedVariation);
+                }
+                if let Some(last) = moves.last_mut() {
+                    last.variations.push(variation);
+                }
+            }
+            Token::Close => {
+                if depth == 0 {
+                    return Err(PgnError::UnbalancedVariation);
+                }
+                return Ok((moves, None));
+            }
+        }
+        *idx += 1;
+    }
+    if depth > 0 {
+        return Err(PgnError::UnbalancedVariation);
+    }
+    Ok((moves, None))
+}
+
+pub fn parse_pgn(text: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let tokens = tokenize(text)?;
+    let mut games: Vec<PgnGame> = Vec::new();
+    let mut idx = 0;
+    while idx < tokens.len() {
+        let mut game = PgnGame::default();
+        while let Some(Token::Tag(name, value)) = tokens.get(idx) {
+            game.tags.push((name.clone(), value.clone()));
+            idx += 1;
+        }
+        let (moves, result) = parse_line(&tokens, &mut idx, 0)?;
+        game.moves = moves;
+        game.result = result
+            .or_else(|| game.tag("Result").map(|r| r.to_string()))
+            .unwrap_or("*".to_string());
+        games.push(game);
+    }
+    if games.is_empty() {
+        return Err(PgnError::NoGame);
+    }
+    Ok(games)
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // plays the first `plies` moves of the main line from the game's starting position
+    pub fn to_game(&self, grid: &mut Grid, plies: usize) -> Result<Game, PgnError> {
+        let fen = self.tag("FEN").unwrap_or(STARTING_FEN);
+        let mut game = Game::from_fen(grid, fen).map_err(PgnError::Fen)?;
+        for (index, played) in self.moves.iter().take(plies).enumerate() {
+            let illegal = || PgnError::IllegalMove {
+                index,
+                san: played.san.clone(),
+            };
+            let (from, to, promotion) =
+                san::resolve(grid, &game, &played.san).ok_or_else(illegal)?;
+            if !game.make_move(grid, &from, &to, promotion) {
+                return Err(illegal());
+            }
+        }
+        Ok(game)
+    }
+}
@@ -0,0 +1,364 @@
+use crate::textures::PieceTxts;
+use chess::drawing::{Drawings, MarkColor};
+use chess::game::Game;
+use chess::grid::{CellId, Grid};
+use chess::pieces::PieceType;
+use chess::theme::{BoardTheme, Rgba};
+use macroquad::prelude::*;
+use std::f32::consts::PI;
+
+// how each kind of highlight is coloured
+pub struct HighlightColors {
+    pub last_move: Color,
+    pub check: Color,
+    pub selected: Color,
+    pub move_dot: Color,
+    pub capture_ring: Color,
+    // arrows and circles, green, red, yellow and blue
+    pub marks: [Color; 4],
+}
+
+impl HighlightColors {
+    pub fn mark(&self, color: MarkColor) -> Color {
+        let n = match color {
+            MarkColor::Green => 0,
+            MarkColor::Red => 1,
+            MarkColor::Yellow => 2,
+            MarkColor::Blue => 3,
+        };
+        self.marks[n]
+    }
+}
+
+impl Default for HighlightColors {
+    fn default() -> HighlightColors {
+        HighlightColors {
+            last_move: Color::new(0.9, 0.8, 0.2, 0.4),
+            check: Color::new(1.0, 0.1, 0.1, 1.0),
+            selected: Color::new(0.2, 1.0, 1.0, 0.35),
+            move_dot: Color::new(0.0, 0.0, 0.0, 0.25),
+            capture_ring: Color::new(0.0, 0.0, 0.0, 0.25),
+            marks: [
+                Color::new(0.1, 0.6, 0.2, 0.8),
+                Color::new(0.8, 0.15, 0.1, 0.8),
+                Color::new(0.9, 0.7, 0.0, 0.8),
+                Color::new(0.1, 0.4, 0.8, 0.8),
+            ],
+        }
+    }
+}
+
+// the squares picked out on the board this frame
+#[derive(Default)]
+pub struct Highlights {
+    pub last_move: Option<(CellId, CellId)>,
+    pub checked_king: Option<CellId>,
+    pub selected: Option<CellId>,
+    // where the selected piece can go, true where it takes something
+    pub targets: Vec<(CellId, bool)>,
+    pub drawings: Drawings,
+}
+
+impl Highlights {
+    pub fn of(
+        grid: &Grid,
+        game: &Game,
+        selected: Option<CellId>,
+        drawings: &Drawings,
+    ) -> Highlights {
+        let mut targets = Vec::new();
+        if let Some(from) = selected {
+            let cell = grid.get_cell(&from);
+            let pawn = cell
+                .item
+                .as_ref()
+                .is_some_and(|piece| piece.piece_type == PieceType::Pawn);
+            for to in cell.valid_moves.iter().flatten() {
+                let en_passant = pawn && (to.0 != from.0);
+                targets.push((*to, grid.get_cell(to).item.is_some() || en_passant));
+            }
+        }
+        Highlights {
+            last_move: game.last_played(),
+            checked_king: game.checked.as_ref().map(|check| check.king),
+            selected,
+            targets,
+            drawings: drawings.clone(),
+        }
+    }
+}
+
+// screen side of the board: maps cells to render target coordinates and draws them
+pub struct BoardView {
+    cell_size: f32,
+    light: Color,
+    dark: Color,
+    pub colors: HighlightColors,
+    // file letters and rank numbers along the bottom and left edges
+    pub coordinates: bool,
+}
+
+impl BoardView {
+    pub fn new(cell_size: f32) -> BoardView {
+        BoardView {
+            cell_size,
+            light: WHITE,
+            dark: GRAY,
+            colors: HighlightColors::default(),
+            coordinates: true,
+        }
+    }
+
+    // the squares and highlights in a theme's colours, the usual ones where it
+    // gives none
+    pub fn set_theme(&mut self, theme: &BoardTheme) {
+        let color = |[r, g, b, a]: Rgba| Color::from_rgba(r, g, b, a);
+        let usual = HighlightColors::default();
+        self.light = color(theme.light);
+        self.dark = color(theme.dark);
+        self.colors = HighlightColors {
+            last_move: theme.last_move.map_or(usual.last_move, color),
+            check: theme.check.map_or(usual.check, color),
+            selected: theme.selected.map_or(usual.selected, color),
+            move_dot: theme.move_dot.map_or(usual.move_dot, color),
+            capture_ring: theme.capture_ring.map_or(usual.capture_ring, color),
+            marks: usual.marks,
+        };
+    }
+
+    pub fn origin(&self, id: &CellId) -> (f32, f32) {
+        (id.0 as f32 * self.cell_size, id.1 as f32 * self.cell_size)
+    }
+
+    pub fn cell_color(&self, id: &CellId) -> Color {
+        if (id.0 + id.1).is_multiple_of(2) {
+            self.light
+        } else {
+            self.dark
+        }
+    }
+
+    // the board, its square highlights, the pieces but for a `lifted` one, then
+    // the marks on where the selected piece can go
+    pub fn draw(
+        &self,
+        grid: &Grid,
+        piecetxts: &PieceTxts,
+        flip: bool,
+        lifted: Option<&CellId>,
+        highlights: &Highlights,
+    ) {
+        for idx in 0..64 {
+            let id = CellId::from_vec_idx(idx);
+            let (x, y) = self.origin(&id);
+            draw_rectangle(x, y, self.cell_size, self.cell_size, self.cell_color(&id));
+        }
+        if let Some((from, to)) = &highlights.last_move {
+            self.fill(from, self.colors.last_move);
+            self.fill(to, self.colors.last_move);
+        }
+        if let Some(id) = &highlights.selected {
+            self.fill(id, self.colors.selected);
+        }
+        if let Some(id) = &highlights.checked_king {
+            self.glow(id, self.colors.check);
+        }
+        if self.coordinates {
+            self.draw_coordinates(flip);
+        }
+        for idx in 0..64 {
+            let id = CellId::from_vec_idx(idx);
+            if lifted == Some(&id) {
+                continue;
+            }
+            if let Some(piece) = &grid.get_cell(&id).item {
+                let txt = piecetxts.get(piece.piece_type, &piece.side);
+                self.draw_piece(txt, self.origin(&id), flip);
+            }
+        }
+        for (id, capture) in &highlights.targets {
+            let (x, y) = self.origin(id);
+            let half = self.cell_size / 2.0;
+            if *capture {
+                let thickness = self.cell_size * 0.08;
+                draw_circle_lines(
+                    x + half,
+                    y + half,
+                    half - thickness / 2.0,
+                    thickness,
+                    self.colors.capture_ring,
+                );
+            } else {
+                draw_circle(x + half, y + half, half * 0.3, self.colors.move_dot);
+            }
+        }
+        for (id, color) in &highlights.drawings.circles {
+            let (x, y) = self.origin(id);
+            let half = self.cell_size / 2.0;
+            let thickness = self.cell_size * 0.06;
+            draw_circle_lines(
+                x + half,
+                y + half,
+                half - thickness,
+                thickness,
+                self.colors.mark(*color),
+            );
+        }
+        for (from, to, color) in &highlights.drawings.arrows {
+            self.arrow(from, to, self.colors.mark(*color));
+        }
+    }
+
+    // from the middle of one square to the next, its head ending short of the middle
+    fn arrow(&self, from: &CellId, to: &CellId, color: Color) {
+        let centre = |id: &CellId| {
+            let (x, y) = self.origin(id);
+            vec2(x, y) + vec2(self.cell_size, self.cell_size) / 2.0
+        };
+        let (start, end) = (centre(from), centre(to));
+        let direction = (end - start).normalize();
+        let side = direction.perp();
+        let head = self.cell_size * 0.4;
+        let tip = end - direction * self.cell_size * 0.15;
+        let base = tip - direction * head;
+        draw_line(
+            start.x,
+            start.y,
+            base.x,
+            base.y,
+            self.cell_size * 0.15,
+            color,
+        );
+        draw_triangle(
+            tip,
+            base + side * head * 0.6,
+            base - side * head * 0.6,
+            color,
+        );
+    }
+
+    // the files along the bottom of the board and the ranks up its left side as
+    // they are seen, each in the colour of the other kind of square
+    fn draw_coordinates(&self, flip: bool) {
+        let font_size = self.cell_size * 0.22;
+        let pad = self.cell_size * 0.06;
+        for n in 0..8 {
+            // the n-th square from the left on the bottom row, and from the top
+            // in the left column
+            let (bottom, left) = if flip {
+                (CellId(7 - n, 0), CellId(7, 7 - n))
+            } else {
+                (CellId(n, 7), CellId(0, n))
+            };
+            let file = ((b'a' + bottom.0 as u8) as char).to_string();
+            let width = measure_text(&file, None, font_size as u16, 1.0).width;
+            let at = vec2(
+                (n + 1) as f32 * self.cell_size - pad - width,
+                8.0 * self.cell_size - pad,
+            );
+            self.label(&file, at, font_size, self.other_color(&bottom), flip);
+            let rank = (8 - left.1).to_string();
+            let at = vec2(pad, n as f32 * self.cell_size + pad + font_size * 0.7);
+            self.label(&rank, at, font_size, self.other_color(&left), flip);
+        }
+    }
+
+    fn other_color(&self, id: &CellId) -> Color {
+        if self.cell_color(id) == self.light {
+            self.dark
+        } else {
+            self.light
+        }
+    }
+
+    // text upright on screen from `at` as seen, with the camera turned or not
+    fn label(&self, text: &str, at: Vec2, font_size: f32, color: Color, flip: bool) {
+        let size = self.cell_size * 8.0;
+        let (at, rotation) = if flip {
+            (vec2(size, size) - at, PI)
+        } else {
+            (at, 0.0)
+        };
+        draw_text_ex(
+            text,
+            at.x,
+            at.y,
+            TextParams {
+                font_size: font_size as u16,
+                color,
+                rotation,
+                ..Default::default()
+            },
+        );
+    }
+
+    fn fill(&self, id: &CellId, color: Color) {
+        let (x, y) = self.origin(id);
+        draw_rectangle(x, y, self.cell_size, self.cell_size, color);
+    }
+
+    // fades out from the middle of the square, rings of the colour stacking up
+    fn glow(&self, id: &CellId, color: Color) {
+        let (x, y) = self.origin(id);
+        let half = self.cell_size / 2.0;
+        let rings = 8;
+        for ring in 0..rings {
+            let radius = half * (1.0 - ring as f32 / rings as f32);
+            let color = Color {
+                a: color.a / rings as f32,
+                ..color
+            };
+            draw_circle(x + half, y + half, radius, color);
+        }
+    }
+
+    pub fn draw_piece(&self, txt: &Texture2D, (x, y): (f32, f32), flip: bool) {
+        draw_texture_ex(
+            txt,
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                rotation: if flip { PI } else { 0.0 },
+                dest_size: Some(vec2(self.cell_size, self.cell_size)),
+                ..Default::default()
+            },
+        );
+    }
+
+    // a piece off its square at full size, centred on `at`
+    pub fn draw_lifted(&self, txt: &Texture2D, at: Vec2, flip: bool) {
+        let half = self.cell_size / 2.0;
+        self.draw_piece(txt, (at.x - half, at.y - half), flip);
+    }
+
+    pub fn draw_choice(&self, id: &CellId, txt: &Texture2D, flip: bool) {
+        let (x, y) = self.origin(id);
+        draw_rectangle(
+            x,
+            y,
+            self.cell_size,
+            self.cell_size,
+            Color::new(0.9, 0.9, 0.9, 1.0),
+        );
+        self.draw_piece(txt, (x, y), flip);
+    }
+
+    pub fn dim(&self) {
+        let size = self.cell_size * 8.0;
+        draw_rectangle(0.0, 0.0, size, size, Color::new(0.0, 0.0, 0.0, 0.5));
+    }
+
+    pub fn coord_to_cell_id(&self, (xm, ym): (f32, f32)) -> Option<CellId> {
+        if (xm < 0.0) | (ym < 0.0) {
+            return None;
+        }
+        let w = self.cell_size;
+        let id: CellId = CellId((xm / w).floor() as u32, (ym / w).floor() as u32);
+        if id.is_valid() {
+            Some(id)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use chess::game::Game;
+use chess::grid::Grid;
+use chess::pgn::{parse_pgn, PgnError, PgnGame};
+use macroquad::prelude::*;
+
+// read-only walk through the main line of a loaded PGN game
+pub struct Replay {
+    record: PgnGame,
+    pub ply: usize,
+    // ply the game starts at, odd when Black moves first
+    pub first_ply: u32,
+}
+
+impl Replay {
+    pub fn load(path: &str, grid: &mut Grid) -> Result<(Replay, Game), String> {
+        let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let mut record = parse_pgn(&text)
+            .map_err(|err| err.to_string())?
+            .swap_remove(0);
+        // keep the playable part of a game with a broken move
+        if let Err(err) = record.to_game(grid, record.moves.len()) {
+            println!("{}: {}", path, err);
+            let PgnError::IllegalMove { index, .. } = err else {
+                return Err(err.to_string());
+            };
+            record.moves.truncate(index);
+        }
+        let game = record.to_game(grid, 0).map_err(|err| err.to_string())?;
+        let replay = Replay {
+            record,
+            ply: 0,
+            first_ply: game.move_count,
+        };
+        Ok((replay, game))
+    }
+
+    pub fn len(&self) -> usize {
+        self.record.moves.len()
+    }
+
+    pub fn sans(&self) -> Vec<&str> {
+        self.record
+            .moves
+            .iter()
+            .map(|played| played.san.as_str())
+            .collect()
+    }
+
+    pub fn key_handler(&mut self, grid: &mut Grid, game: &mut Game) {
+        let ply = if is_key_pressed(KeyCode::Right) {
+            (self.ply + 1).min(self.len())
+        } else if is_key_pressed(KeyCode::Left) {
+            self.ply.saturating_sub(1)
+        } else if is_key_pressed(KeyCode::Home) {
+            0
+        } else if is_key_pressed(KeyCode::End) {
+            self.len()
+        } else {
+            return;
+        };
+        self.jump(grid, game, ply);
+    }
+
+    // shows the position after the first `ply` moves
+    pub fn jump(&mut self, grid: &mut Grid, game: &mut Game, ply: usize) {
+        if ply == self.ply {
+            return;
+        }
+        self.ply = ply;
+        *game = self.record.to_game(grid, ply).unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+use crate::game::{Game, Promotion};
+use crate::grid::{CellId, Grid};
+use crate::path::Direction;
+use crate::pieces::PieceType;
+
+pub fn piece_letter(piece_type: PieceType) -> char {
+    piece_type.to_char().to_ascii_uppercase()
+}
+
+// coordinate notation as spoken by UCI engines, e.g. e2e4 or e7e8q
+pub fn coordinate(from: &CellId, to: &CellId, promotion: Option<PieceType>) -> String {
+    let mut notation = format!("{}{}", from.to_square(), to.to_square());
+    if let Some(piece_type) = promotion {
+        notation.push(piece_type.to_char());
+    }
+    notation
+}
+
+pub fn parse_coordinate(notation: &str) -> Option<(CellId, CellId, Option<PieceType>)> {
+    let from = CellId::from_square(notation.get(0..2)?)?;
+    let to = CellId::from_square(notation.get(2..4)?)?;
+    let promotion = match notation.get(4..)? {
+        "" => None,
+        piece => {
+            let mut chars = piece.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return None;
+            };
+            Some(PieceType::from_char(c)?)
+        }
+    };
+    Some((from, to, promotion))
+}
+
+// Standard Algebraic Notation for a move that is about to be played, without the
+// promotion piece and check suffix which are only known once the turn is over.
+// Relies on the valid_moves cached for the side to move for disambiguation.
+pub fn describe(grid: &Grid, from: &CellId, to: &CellId) -> String {
+    let Some(piece) = &grid.get_cell(from).item else {
+        return String::new();
+    };
+    let dest = to.to_square();
+    match piece.piece_type {
+        PieceType::King if from.0.abs_diff(to.0) == 2 => {
+            if to.0 > from.0 {
+                "O-O".to_string()
+            } else {
+                "O-O-O".to_string()
+            }
+        }
+        PieceType::Pawn => {
+            // a pawn changing file always captures, en passant included
+            if from.0 != to.0 {
+                format!("{}x{}", &from.to_square()[..1], dest)
+            } else {
+                dest
+            }
+        }
+        _ => {
+            let capture = if grid.get_cell(to).item.is_some() {
+                "x"
+            } else {
+                ""
+            };
+            format!(
+                "{}{}{}{}",
+                piece_letter(piece.piece_type),
+                disambiguation(grid, from, to),
+                capture,
+                dest
+            )
+        }
+    }
+}
+
+fn disambiguation(grid: &Grid, from: &CellId, to: &CellId) -> String {
+    let piece = grid.get_cell(from).item.as_ref().unwrap();
+    let mut rivals: Vec<CellId> = Vec::new();
+    for idx in 0..64 {
+        let id = CellId::from_vec_idx(idx);
+        if id == *from {
+            continue;
+        }
+        let cell = grid.get_cell(&id);
+        let Some(other) = &cell.item else {
+            continue;
+        };
+        if (other.piece_type != piece.piece_type) | (other.side != piece.side) {
+            continue;
+        }
+        let Some(valid_moves) = &cell.valid_moves else {
+            continue;
+        };
+        if valid_moves.contains(to) {
+            rivals.push(id);
+        }
+    }
+    if rivals.is_empty() {
+        return String::new();
+    }
+    let square = from.to_square();
+    if rivals.iter().all(|id| id.0 != from.0) {
+        square[..1].to_string()
+    } else if rivals.iter().all(|id| id.1 != from.1) {
+        square[1..].to_string()
+    } else {
+        square
+    }
+}
+
+// finds the move a SAN string refers to among the valid moves of the side to move,
+// as (from, to, promotion piece)
+pub fn resolve(grid: &Grid, game: &Game, san: &str) -> Option<(CellId, CellId, Option<PieceType>)> {
+    let san = san.trim_end_matches(['+', '#', '!', '?']);
+    let king = game.king_now();
+    let castle_to = match san {
+        "O-O" | "0-0" => king.try_next_cellid(Direction::Right, 2),
+        "O-O-O" | "0-0-0" => king.try_next_cellid(Direction::Left, 2),
+        _ => None,
+    };
+    if let Some(dest) = castle_to {
+        let cell = grid.get_cell(&king);
+        let valid_moves = cell.valid_moves.as_ref()?;
+        return valid_moves.contains(&dest).then_some((king, dest, None));
+    }
+
+    let mut chars: Vec<char> = san.chars().filter(|c| (*c != 'x') & (*c != '-')).collect();
+    let mut promotion: Option<PieceType> = None;
+    if let Some(n) = chars.iter().position(|c| *c == '=') {
+        promotion = Some(PieceType::from_char(*chars.get(n + 1)?)?);
+        chars.truncate(n);
+    } else if chars.len() > 2 && chars[chars.len() - 2].is_ascii_digit() {
+        // promotion written without '=', e.g. e8Q
+        promotion = Some(PieceType::from_char(chars.pop()?)?);
+    }
+    if matches!(promotion, Some(PieceType::King | PieceType::Pawn)) {
+        return None;
+    }
+    let piece_type = match chars.first() {
+        Some(c) if c.is_ascii_uppercase() => {
+            let piece_type = PieceType::from_char(*c)?;
+            chars.remove(0);
+            piece_type
+        }
+        _ => PieceType::Pawn,
+    };
+    if chars.len() < 2 {
+        return None;
+    }
+    let dest: String = chars.split_off(chars.len() - 2).into_iter().collect();
+    let dest = CellId::from_square(&dest)?;
+    let mut file_hint: Option<u32> = None;
+    let mut rank_hint: Option<u32> = None;
+    for c in chars {
+        match c {
+            'a'..='h' => file_hint = Some(c as u32 - 'a' as u32),
+            '1'..='8' => rank_hint = Some(8 - c.to_digit(10)?),
+            _ => return None,
+        }
+    }
+
+    let mut found: Option<CellId> = None;
+    for idx in 0..64 {
+        let id = CellId::from_vec_idx(idx);
+        let cell = grid.get_cell(&id);
+        let Some(piece) = &cell.item else {
+            continue;
+        };
+        if (piece.piece_type != piece_type) | (piece.side != game.turn()) {
+            continue;
+        }
+        if file_hint.is_some_and(|x| x != id.0) | rank_hint.is_some_and(|y| y != id.1) {
+            continue;
+        }
+        let Some(valid_moves) = &cell.valid_moves else {
+            continue;
+        };
+        if !valid_moves.contains(&dest) {
+            continue;
+        }
+        if found.is_some() {
+            // ambiguous
+            return None;
+        }
+        found = Some(id);
+    }
+    let from = found?;
+    let piece = grid.get_cell(&from).item.as_ref()?;
+    if Promotion::is(piece, &dest) != promotion.is_some() {
+        return None;
+    }
+    Some((from, dest, promotion))
+}
@@ -0,0 +1,36 @@
+use chess::game::Game;
+use chess::pgn::PgnTags;
+use macroquad::miniquad::date;
+
+// days since the unix epoch to (year, month, day), after Howard Hinnant's civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+pub fn today() -> String {
+    let (year, month, day) = civil_from_days(date::now() as i64 / 86400);
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+pub fn save_pgn(game: &Game) {
+    let tags = PgnTags {
+        event: "Casual game".to_string(),
+        site: "chess".to_string(),
+        date: today(),
+        ..Default::default()
+    };
+    let path = format!("game-{}.pgn", date::now() as u64);
+    match std::fs::write(&path, game.to_pgn(&tags)) {
+        Ok(_) => println!("saved {}", path),
+        Err(err) => println!("could not save {}: {}", path, err),
+    }
+}
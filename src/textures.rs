@@ -1,3 +1,5 @@
+use chess::pieces::{PieceType, Side};
+use chess::theme::{parse_themes, piece_sets, BoardTheme};
 use macroquad::prelude::*;
 pub struct PieceTxts {
     pub pawn_w: Texture2D,
@@ -14,25 +16,26 @@ pub struct PieceTxts {
     pub knight_b: Texture2D,
 }
 impl PieceTxts {
-    pub async fn default() -> PieceTxts {
-        let pawn_w: Texture2D = load_texture("tatiana/pw.png").await.unwrap();
-        let pawn_b: Texture2D = load_texture("tatiana/pb.png").await.unwrap();
+    // the twelve pictures of a piece set, from its directory under assets
+    pub async fn load(set: &str) -> Result<PieceTxts, macroquad::Error> {
+        let pawn_w = load_texture(&format!("{}/pw.png", set)).await?;
+        let pawn_b = load_texture(&format!("{}/pb.png", set)).await?;
 
-        let king_w: Texture2D = load_texture("tatiana/kw.png").await.unwrap();
-        let king_b: Texture2D = load_texture("tatiana/kb.png").await.unwrap();
+        let king_w = load_texture(&format!("{}/kw.png", set)).await?;
+        let king_b = load_texture(&format!("{}/kb.png", set)).await?;
 
-        let bishop_w: Texture2D = load_texture("tatiana/bw.png").await.unwrap();
-        let bishop_b: Texture2D = load_texture("tatiana/bb.png").await.unwrap();
+        let bishop_w = load_texture(&format!("{}/bw.png", set)).await?;
+        let bishop_b = load_texture(&format!("{}/bb.png", set)).await?;
 
-        let rook_w: Texture2D = load_texture("tatiana/rw.png").await.unwrap();
-        let rook_b: Texture2D = load_texture("tatiana/rb.png").await.unwrap();
+        let rook_w = load_texture(&format!("{}/rw.png", set)).await?;
+        let rook_b = load_texture(&format!("{}/rb.png", set)).await?;
 
-        let queen_w: Texture2D = load_texture("tatiana/qw.png").await.unwrap();
-        let queen_b: Texture2D = load_texture("tatiana/qb.png").await.unwrap();
+        let queen_w = load_texture(&format!("{}/qw.png", set)).await?;
+        let queen_b = load_texture(&format!("{}/qb.png", set)).await?;
 
-        let knight_w: Texture2D = load_texture("tatiana/nw.png").await.unwrap();
-        let knight_b: Texture2D = load_texture("tatiana/nb.png").await.unwrap();
-        PieceTxts {
+        let knight_w = load_texture(&format!("{}/nw.png", set)).await?;
+        let knight_b = load_texture(&format!("{}/nb.png", set)).await?;
+        Ok(PieceTxts {
             pawn_w,
             pawn_b,
             king_b,
@@ -45,6 +48,48 @@ impl PieceTxts {
             queen_w,
             knight_b,
             knight_w,
+        })
+    }
+
+    pub fn get(&self, piece_type: PieceType, side: &Side) -> &Texture2D {
+        match (piece_type, side) {
+            (PieceType::Pawn, Side::White) => &self.pawn_w,
+            (PieceType::Pawn, Side::Black) => &self.pawn_b,
+            (PieceType::King, Side::White) => &self.king_w,
+            (PieceType::King, Side::Black) => &self.king_b,
+            (PieceType::Bishop, Side::White) => &self.bishop_w,
+            (PieceType::Bishop, Side::Black) => &self.bishop_b,
+            (PieceType::Rook, Side::White) => &self.rook_w,
+            (PieceType::Rook, Side::Black) => &self.rook_b,
+            (PieceType::Queen, Side::White) => &self.queen_w,
+            (PieceType::Queen, Side::Black) => &self.queen_b,
+            (PieceType::Knight, Side::White) => &self.knight_w,
+            (PieceType::Knight, Side::Black) => &self.knight_b,
         }
     }
 }
+
+// the piece sets under assets; the web build can't look, so it has the one
+pub fn piece_set_names() -> Vec<String> {
+    let sets = piece_sets(std::path::Path::new("assets"));
+    if sets.is_empty() {
+        vec!["tatiana".to_string()]
+    } else {
+        sets
+    }
+}
+
+// the board themes in assets/themes.txt, or the plain board if it can't be read
+pub async fn board_themes() -> Vec<BoardTheme> {
+    let text = match load_string("themes.txt").await {
+        Ok(text) => text,
+        Err(err) => {
+            println!("could not load themes.txt: {}", err);
+            return vec![BoardTheme::default()];
+        }
+    };
+    parse_themes(&text).unwrap_or_else(|err| {
+        println!("could not read themes.txt: {}", err);
+        vec![BoardTheme::default()]
+    })
+}
@@ -0,0 +1,176 @@
+use std::fmt;
+use std::path::Path;
+
+// red, green, blue and alpha, 0 to 255
+pub type Rgba = [u8; 4];
+
+// the files a piece set's directory has to hold, one per piece and colour
+pub const PIECE_FILES: [&str; 12] = [
+    "pw", "pb", "kw", "kb", "bw", "bb", "rw", "rb", "qw", "qb", "nw", "nb",
+];
+
+// the directories under `assets` holding a complete piece set, by name
+pub fn piece_sets(assets: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(assets) else {
+        return Vec::new();
+    };
+    let mut sets: Vec<String> = entries
+        .flatten()
+        .filter(|entry| {
+            let dir = entry.path();
+            PIECE_FILES
+                .iter()
+                .all(|file| dir.join(format!("{}.png", file)).is_file())
+        })
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    sets.sort();
+    sets
+}
+
+// the colours of a board; highlights left out keep their usual colour
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardTheme {
+    pub name: String,
+    pub light: Rgba,
+    pub dark: Rgba,
+    pub last_move: Option<Rgba>,
+    pub check: Option<Rgba>,
+    pub selected: Option<Rgba>,
+    pub move_dot: Option<Rgba>,
+    pub capture_ring: Option<Rgba>,
+}
+
+impl Default for BoardTheme {
+    fn default() -> BoardTheme {
+        BoardTheme {
+            name: "classic".to_string(),
+            light: [255, 255, 255, 255],
+            dark: [128, 128, 128, 255],
+            last_move: None,
+            check: None,
+            selected: None,
+            move_dot: None,
+            capture_ring: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ThemeError {
+    NoThemes,
+    OutsideTheme(usize),
+    NotAColor { line: usize, key: String },
+    UnknownKey { line: usize, key: String },
+    InvalidColor { line: usize, value: String },
+    MissingColor { theme: String, key: &'static str },
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThemeError::NoThemes => write!(f, "no themes found"),
+            ThemeError::OutsideTheme(line) => {
+                write!(f, "line {}: colour given before any [theme]", line)
+            }
+            ThemeError::NotAColor { line, key } => {
+                write!(
+                    f,
+                    "line {}: expected 'name = colour', found '{}'",
+                    line, key
+                )
+            }
+            ThemeError::UnknownKey { line, key } => {
+                write!(f, "line {}: '{}' is not a board colour", line, key)
+            }
+            ThemeError::InvalidColor { line, value } => {
+                write!(f, "line {}: '{}' is not a #rrggbb colour", line, value)
+            }
+            ThemeError::MissingColor { theme, key } => {
+                write!(f, "theme '{}' has no {} colour", theme, key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+// "#rrggbb", or "#rrggbbaa" for a see-through colour
+pub fn parse_color(value: &str) -> Option<Rgba> {
+    let hex = value.strip_prefix('#')?;
+    if !hex.is_ascii() || !matches!(hex.len(), 6 | 8) {
+        return None;
+    }
+    let mut color = [255; 4];
+    for (i, channel) in color.iter_mut().enumerate().take(hex.len() / 2) {
+        *channel = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(color)
+}
+
+// board themes as `[name]` headers each followed by `key = #rrggbb` lines;
+// `light` and `dark` are needed, `#` starts a comment line
+pub fn parse_themes(text: &str) -> Result<Vec<BoardTheme>, ThemeError> {
+    // light and dark squares as they are read, checked when the theme ends
+    let mut themes: Vec<(BoardTheme, Option<Rgba>, Option<Rgba>)> = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let theme = BoardTheme {
+                name: name.trim().to_string(),
+                ..Default::default()
+            };
+            themes.push((theme, None, None));
+            continue;
+        }
+        let Some((theme, light, dark)) = themes.last_mut() else {
+            return Err(ThemeError::OutsideTheme(n + 1));
+        };
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(ThemeError::NotAColor {
+                line: n + 1,
+                key: line.to_string(),
+            });
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let color = parse_color(value).ok_or_else(|| ThemeError::InvalidColor {
+            line: n + 1,
+            value: value.to_string(),
+        })?;
+        match key {
+            "light" => *light = Some(color),
+            "dark" => *dark = Some(color),
+            "last_move" => theme.last_move = Some(color),
+            "check" => theme.check = Some(color),
+            "selected" => theme.selected = Some(color),
+            "move_dot" => theme.move_dot = Some(color),
+            "capture_ring" => theme.capture_ring = Some(color),
+            _ => {
+                return Err(ThemeError::UnknownKey {
+                    line: n + 1,
+                    key: key.to_string(),
+                })
+            }
+        }
+    }
+    if themes.is_empty() {
+        return Err(ThemeError::NoThemes);
+    }
+    themes
+        .into_iter()
+        .map(|(theme, light, dark)| {
+            let missing = |key| ThemeError::MissingColor {
+                theme: theme.name.clone(),
+                key,
+            };
+            Ok(BoardTheme {
+                light: light.ok_or_else(|| missing("light"))?,
+                dark: dark.ok_or_else(|| missing("dark"))?,
+                ..theme.clone()
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,260 @@
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::ai::{search, Limits, SearchInfo, MATE, MAX_DEPTH};
+use crate::game::{Game, STARTING_FEN};
+use crate::grid::Grid;
+use crate::pieces::Side;
+use crate::san;
+
+// moves assumed left in the game when the GUI does not say
+const MOVES_TO_GO: u32 = 30;
+// kept back from the clock for the trip to the GUI and back
+const TIME_MARGIN: Duration = Duration::from_millis(50);
+
+// what a `go` command asked for
+#[derive(Debug, Default, PartialEq)]
+pub struct Go {
+    pub depth: Option<u32>,
+    pub movetime: Option<Duration>,
+    pub wtime: Option<Duration>,
+    pub btime: Option<Duration>,
+    pub winc: Option<Duration>,
+    pub binc: Option<Duration>,
+    pub movestogo: Option<u32>,
+    pub infinite: bool,
+}
+
+impl Go {
+    pub fn parse(args: &[&str]) -> Go {
+        let mut go = Go::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut number = || args.next().and_then(|value| value.parse::<u64>().ok());
+            match *arg {
+                "depth" => go.depth = number().map(|depth| depth as u32),
+                "movetime" => go.movetime = number().map(Duration::from_millis),
+                "wtime" => go.wtime = number().map(Duration::from_millis),
+                "btime" => go.btime = number().map(Duration::from_millis),
+                "winc" => go.winc = number().map(Duration::from_millis),
+                "binc" => go.binc = number().map(Duration::from_millis),
+                "movestogo" => go.movestogo = number().map(|moves| moves as u32),
+                "infinite" => go.infinite = true,
+                _ => {}
+            }
+        }
+        go
+    }
+
+    // search limits for the side to move, sharing its clock out over the moves to go
+    pub fn limits(&self, turn: Side) -> Limits {
+        let (time, increment) = match turn {
+            Side::White => (self.wtime, self.winc),
+            Side::Black => (self.btime, self.binc),
+        };
+        let movetime = if self.infinite {
+            None
+        } else if self.movetime.is_some() {
+            self.movetime
+        } else {
+            time.map(|time| {
+                let share = time / self.movestogo.unwrap_or(MOVES_TO_GO).max(1)
+                    + increment.unwrap_or_default() * 3 / 4;
+                share.min(time.saturating_sub(TIME_MARGIN))
+            })
+        };
+        Limits {
+            depth: self.depth,
+            movetime,
+        }
+    }
+}
+
+// score as UCI words it, in centipawns or moves to mate
+fn score(score: i32) -> String {
+    let to_mate = MATE - score.abs();
+    if to_mate > MAX_DEPTH as i32 {
+        return format!("cp {}", score);
+    }
+    let moves = (to_mate + 1) / 2;
+    format!("mate {}", if score > 0 { moves } else { -moves })
+}
+
+fn info_line(info: &SearchInfo, elapsed: Duration) -> String {
+    let millis = elapsed.as_millis().max(1) as u64;
+    let mut line = format!(
+        "info depth {} score {} nodes {} nps {} time {}",
+        info.depth,
+        score(info.score),
+        info.nodes,
+        info.nodes * 1000 / millis,
+        millis,
+    );
+    if let Some(best) = info.best {
+        line.push_str(" pv ");
+        line.push_str(&san::coordinate(
+            &best.from_id(),
+            &best.to_id(),
+            best.promotion,
+        ));
+    }
+    line
+}
+
+fn send<W: Write>(output: &Mutex<W>, line: &str) {
+    let mut output = output.lock().unwrap();
+    let _ = writeln!(output, "{}", line);
+    let _ = output.flush();
+}
+
+struct Searching {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+    infinite: bool,
+}
+
+// an engine session: the game the GUI set up and the search running on it, if any
+pub struct Uci<W: Write + Send + 'static> {
+    grid: Grid,
+    game: Game,
+    output: Arc<Mutex<W>>,
+    searching: Option<Searching>,
+}
+
+impl<W: Write + Send + 'static> Uci<W> {
+    pub fn new(output: W) -> Uci<W> {
+        let mut grid = Grid::new64();
+        let game = Game::new(&mut grid);
+        Uci {
+            grid,
+            game,
+            output: Arc::new(Mutex::new(output)),
+            searching: None,
+        }
+    }
+
+    // handles one line from the GUI, false once it says quit
+    pub fn command(&mut self, line: &str) -> bool {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((command, args)) = words.split_first() else {
+            return true;
+        };
+        match *command {
+            "uci" => {
+                send(&self.output, &format!("id name {}", env!("CARGO_PKG_NAME")));
+                send(&self.output, "id author colonelkrab");
+                send(&self.output, "uciok");
+            }
+            "isready" => send(&self.output, "readyok"),
+            "ucinewgame" => {
+                self.stop();
+                self.game = Game::new(&mut self.grid);
+            }
+            "position" => {
+                self.stop();
+                if let Err(err) = self.position(args) {
+                    send(&self.output, &format!("info string {}", err));
+                }
+            }
+            "go" => {
+                self.stop();
+                self.go(Go::parse(args));
+            }
+            "stop" => self.stop(),
+            "quit" => {
+                self.stop();
+                return false;
+            }
+            // anything else is ignored, as the protocol asks
+            _ => {}
+        }
+        true
+    }
+
+    // `startpos` or `fen <six fields>`, then optionally `moves` in coordinate notation
+    fn position(&mut self, args: &[&str]) -> Result<(), String> {
+        let moves_at = args
+            .iter()
+            .position(|arg| *arg == "moves")
+            .unwrap_or(args.len());
+        let fen = match args.first() {
+            Some(&"startpos") => STARTING_FEN.to_string(),
+            Some(&"fen") => args[1..moves_at].join(" "),
+            _ => return Err("position needs startpos or fen".to_string()),
+        };
+        self.game = Game::from_fen(&mut self.grid, &fen).map_err(|err| err.to_string())?;
+        for notation in args.iter().skip(moves_at + 1) {
+            let legal = san::parse_coordinate(notation).is_some_and(|(from, to, promotion)| {
+                self.game.make_move(&mut self.grid, &from, &to, promotion)
+            });
+            if !legal {
+                return Err(format!("illegal move {}", notation));
+            }
+        }
+        Ok(())
+    }
+
+    fn go(&mut self, go: Go) {
+        let position = self.game.position;
+        let limits = go.limits(position.turn);
+        let infinite = go.infinite;
+        let stop = Arc::new(AtomicBool::new(false));
+        let output = self.output.clone();
+        let stop_ = stop.clone();
+        let handle = std::thread::spawn(move || {
+            let started = Instant::now();
+            let info = search(&position, &limits, &stop_, |info| {
+                send(&output, &info_line(info, started.elapsed()));
+            });
+            // an infinite search only answers once told to stop
+            while infinite && !stop_.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            let best = match info.best {
+                Some(best) => san::coordinate(&best.from_id(), &best.to_id(), best.promotion),
+                None => "0000".to_string(),
+            };
+            send(&output, &format!("bestmove {}", best));
+        });
+        self.searching = Some(Searching {
+            stop,
+            handle,
+            infinite,
+        });
+    }
+
+    // ends the running search, which still reports its best move
+    pub fn stop(&mut self) {
+        if let Some(searching) = self.searching.take() {
+            searching.stop.store(true, Ordering::Relaxed);
+            let _ = searching.handle.join();
+        }
+    }
+
+    // lets the running search run out by its own limits, an infinite one is stopped
+    pub fn wait(&mut self) {
+        if let Some(searching) = self.searching.take() {
+            if searching.infinite {
+                searching.stop.store(true, Ordering::Relaxed);
+            }
+            let _ = searching.handle.join();
+        }
+    }
+}
+
+// speaks UCI over the given streams until quit or the end of input
+pub fn run(input: impl BufRead, output: impl Write + Send + 'static) {
+    let mut uci = Uci::new(output);
+    for line in input.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if !uci.command(&line) {
+            return;
+        }
+    }
+    uci.wait();
+}
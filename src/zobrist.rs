@@ -0,0 +1,45 @@
+use crate::bitboard::{piece_index, side_index};
+use crate::pieces::{PieceType, Side};
+
+// random keys xor-ed together into a position key: one per piece on a square,
+// then the side to move, the four castling rights and the en passant files
+const PIECE_KEYS: usize = 2 * 6 * 64;
+const BLACK_TO_MOVE_KEY: usize = PIECE_KEYS;
+const CASTLING_KEYS: usize = BLACK_TO_MOVE_KEY + 1;
+const EN_PASSANT_KEYS: usize = CASTLING_KEYS + 4;
+const KEY_COUNT: usize = EN_PASSANT_KEYS + 8;
+
+// splitmix64 from a fixed seed, so keys are the same on every run and build
+const fn keys() -> [u64; KEY_COUNT] {
+    let mut keys = [0; KEY_COUNT];
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut i = 0;
+    while i < KEY_COUNT {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+const KEYS: [u64; KEY_COUNT] = keys();
+
+pub const BLACK_TO_MOVE: u64 = KEYS[BLACK_TO_MOVE_KEY];
+
+pub fn piece(side: Side, piece_type: PieceType, square: usize) -> u64 {
+    KEYS[(side_index(side) * 6 + piece_index(piece_type)) * 64 + square]
+}
+
+// rights as the bitboard castling bits
+pub fn castling(rights: u8) -> u64 {
+    (0..4)
+        .filter(|n| rights & (1 << n) != 0)
+        .fold(0, |key, n| key ^ KEYS[CASTLING_KEYS + n])
+}
+
+pub fn en_passant(square: usize) -> u64 {
+    KEYS[EN_PASSANT_KEYS + square % 8]
+}
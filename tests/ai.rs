@@ -0,0 +1,56 @@
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use chess::ai::{evaluate, search, Limits, MATE};
+use chess::game::{Game, STARTING_FEN};
+use chess::grid::Grid;
+use chess::san;
+
+fn best(fen: &str, depth: u32) -> (String, i32) {
+    let mut grid = Grid::new64();
+    let game = Game::from_fen(&mut grid, fen).unwrap();
+    let limits = Limits {
+        depth: Some(depth),
+        movetime: None,
+    };
+    let info = search(&game.position, &limits, &AtomicBool::new(false), |_| {});
+    let best = info.best.unwrap();
+    (
+        san::coordinate(&best.from_id(), &best.to_id(), best.promotion),
+        info.score,
+    )
+}
+
+#[test]
+fn evaluation_is_symmetric() {
+    let mut grid = Grid::new64();
+    let game = Game::from_fen(&mut grid, STARTING_FEN).unwrap();
+    assert_eq!(evaluate(&game.position), 0);
+    let game = Game::from_fen(&mut grid, "4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+    assert!(evaluate(&game.position) < -800);
+}
+
+#[test]
+fn finds_mate_in_one() {
+    let (played, score) = best("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 2);
+    assert_eq!(played, "a1a8");
+    assert_eq!(score, MATE - 1);
+}
+
+#[test]
+fn takes_a_hanging_queen() {
+    let (played, _) = best("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", 2);
+    assert_eq!(played, "d2d5");
+}
+
+#[test]
+fn stops_when_the_time_is_up() {
+    let mut grid = Grid::new64();
+    let game = Game::new(&mut grid);
+    let limits = Limits {
+        depth: None,
+        movetime: Some(Duration::from_millis(200)),
+    };
+    let info = search(&game.position, &limits, &AtomicBool::new(false), |_| {});
+    assert!(info.best.is_some() && info.depth >= 1);
+}
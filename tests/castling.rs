@@ -0,0 +1,126 @@
+use chess::game::Game;
+use chess::grid::Grid;
+
+mod common;
+use common::sq;
+
+// castling destinations the king of the side to move is offered
+fn castles(fen: &str) -> Vec<String> {
+    let mut grid = Grid::new64();
+    let game = Game::from_fen(&mut grid, fen).unwrap();
+    let king = game.king_now();
+    let mut castles: Vec<String> = grid
+        .get_cell(&king)
+        .valid_moves
+        .as_ref()
+        .unwrap()
+        .iter()
+        .filter(|id| id.0.abs_diff(king.0) == 2)
+        .map(|id| id.to_square())
+        .collect();
+    castles.sort();
+    castles
+}
+
+#[test]
+fn both_sides_when_free() {
+    assert_eq!(
+        castles("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"),
+        ["c1", "g1"]
+    );
+    assert_eq!(
+        castles("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1"),
+        ["c8", "g8"]
+    );
+}
+
+#[test]
+fn not_out_of_check() {
+    assert!(castles("r3k2r/8/8/8/4r3/8/8/R3K2R w KQkq - 0 1").is_empty());
+    assert!(castles("r3k2r/8/8/8/8/3n4/8/R3K2R w KQkq - 0 1").is_empty());
+    assert!(castles("r3k2r/8/8/1B6/8/8/8/R3K2R b KQkq - 0 1").is_empty());
+}
+
+#[test]
+fn not_through_an_attacked_square() {
+    // f1 seen by the bishop
+    assert_eq!(castles("r3k2r/8/8/8/2b5/8/8/R3K2R w KQkq - 0 1"), ["c1"]);
+    // d1 seen by the rook
+    assert_eq!(castles("r3k2r/3r4/8/8/8/8/8/R3K2R w KQkq - 0 1"), ["g1"]);
+    // f8 seen by the knight
+    assert_eq!(castles("r3k2r/7N/8/8/8/8/8/R3K2R b KQkq - 0 1"), ["c8"]);
+    // f1 seen by a pawn
+    assert_eq!(castles("r3k2r/8/8/8/8/8/6p1/R3K2R w KQkq - 0 1"), ["c1"]);
+}
+
+#[test]
+fn not_into_check() {
+    // g1 seen by the bishop
+    assert_eq!(castles("r3k2r/8/8/8/8/8/7b/R3K2R w KQkq - 0 1"), ["c1"]);
+    // c8 seen by the rook
+    assert_eq!(castles("r3k2r/2R5/8/8/8/8/8/R3K2R b KQkq - 0 1"), ["g8"]);
+}
+
+#[test]
+fn attacked_rook_or_b_file_does_not_matter() {
+    // the rooks on a8 and h8 already see a1 and h1
+    assert_eq!(
+        castles("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"),
+        ["c1", "g1"]
+    );
+    // b1 is crossed by the rook only
+    assert_eq!(
+        castles("r3k2r/8/8/8/8/8/b7/R3K2R w KQkq - 0 1"),
+        ["c1", "g1"]
+    );
+}
+
+#[test]
+fn not_with_pieces_in_between() {
+    assert_eq!(
+        castles("r3k2r/8/8/8/8/8/8/RN2K1NR w KQkq - 0 1"),
+        Vec::<String>::new()
+    );
+    assert_eq!(castles("r3k2r/8/8/8/8/8/8/R3KB1R w KQkq - 0 1"), ["c1"]);
+    // a second rook in the way is no castling partner either
+    assert_eq!(castles("r3k2r/8/8/8/8/8/8/RR2K2R w KQkq - 0 1"), ["g1"]);
+    assert_eq!(castles("rn2k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1"), ["g8"]);
+}
+
+#[test]
+fn not_without_rights() {
+    assert_eq!(castles("r3k2r/8/8/8/8/8/8/R3K2R w Kkq - 0 1"), ["g1"]);
+    assert_eq!(
+        castles("r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1"),
+        Vec::<String>::new()
+    );
+    assert_eq!(castles("r3k2r/8/8/8/8/8/8/R3K2R b KQq - 0 1"), ["c8"]);
+}
+
+#[test]
+fn not_after_the_king_or_rook_moved() {
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    for (from, to) in [("e1", "f1"), ("a8", "b8"), ("f1", "e1"), ("b8", "a8")] {
+        assert!(game.play(&mut grid, &sq(from), &sq(to)));
+    }
+    let king = grid.get_cell(&sq("e1")).valid_moves.as_ref().unwrap();
+    assert!(!king.contains(&sq("g1")) && !king.contains(&sq("c1")));
+    assert!(game.play(&mut grid, &sq("a1"), &sq("a2")));
+    let king = grid.get_cell(&sq("e8")).valid_moves.as_ref().unwrap();
+    assert!(king.contains(&sq("g8")) && !king.contains(&sq("c8")));
+}
+
+#[test]
+fn castling_moves_the_rook_and_undoes() {
+    let mut grid = Grid::new64();
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    assert!(game.play(&mut grid, &sq("e1"), &sq("g1")));
+    assert!(game.play(&mut grid, &sq("e8"), &sq("c8")));
+    assert_eq!(game.to_fen(), "2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2");
+    assert_eq!(game.history[0].san, "O-O");
+    assert_eq!(game.history[1].san, "O-O-O");
+    assert!(game.undo(&mut grid) && game.undo(&mut grid));
+    assert_eq!(game.to_fen(), fen);
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use chess::clock::{Clock, Increment, TimeControl};
+use chess::game::{DrawReason, Game, GameResult};
+use chess::grid::{CellId, Grid};
+use chess::pieces::Side;
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+#[test]
+fn fischer_adds_the_increment_every_move() {
+    let mut clock = Clock::new(TimeControl::new(3, 2, Increment::Fischer));
+    clock.tick(Side::White, secs(10));
+    clock.press(Side::White);
+    assert_eq!(clock.remaining(Side::White), secs(172));
+    clock.tick(Side::Black, secs(1));
+    clock.press(Side::Black);
+    assert_eq!(clock.remaining(Side::Black), secs(181));
+}
+
+#[test]
+fn bronstein_gives_back_the_time_used_up_to_the_increment() {
+    let mut clock = Clock::new(TimeControl::new(5, 3, Increment::Bronstein));
+    clock.tick(Side::White, secs(2));
+    clock.press(Side::White);
+    assert_eq!(clock.remaining(Side::White), secs(300));
+    clock.tick(Side::Black, secs(10));
+    clock.press(Side::Black);
+    assert_eq!(clock.remaining(Side::Black), secs(293));
+}
+
+#[test]
+fn delay_runs_before_the_clock_does() {
+    let mut clock = Clock::new(TimeControl::new(10, 5, Increment::Delay));
+    clock.tick(Side::White, secs(3));
+    assert_eq!(clock.remaining(Side::White), secs(600));
+    assert_eq!(clock.delay_left(), secs(2));
+    clock.tick(Side::White, secs(4));
+    assert_eq!(clock.remaining(Side::White), secs(598));
+    clock.press(Side::White);
+    assert_eq!(clock.delay_left(), secs(5));
+    clock.tick(Side::Black, secs(5));
+    assert_eq!(clock.remaining(Side::Black), secs(600));
+}
+
+#[test]
+fn labels() {
+    assert_eq!(TimeControl::new(3, 2, Increment::Fischer).label(), "3+2");
+    assert_eq!(
+        TimeControl::new(5, 3, Increment::Bronstein).label(),
+        "5+3 Bronstein"
+    );
+    assert_eq!(TimeControl::new(10, 5, Increment::Delay).label(), "10 d5");
+}
+
+fn flag_fall(fen: &str) -> Option<GameResult> {
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    game.clock = Some(Clock::new(TimeControl::new(1, 0, Increment::Fischer)));
+    game.tick(secs(59));
+    assert_eq!(game.result, None);
+    game.tick(secs(2));
+    game.result
+}
+
+#[test]
+fn flag_fall_loses_against_mating_material() {
+    assert_eq!(
+        flag_fall("r3k3/8/8/8/8/8/8/4K3 w - - 0 1"),
+        Some(GameResult::Timeout {
+            winner: Side::Black
+        })
+    );
+    assert_eq!(
+        flag_fall("4k3/8/8/8/8/8/8/1NB1K3 b - - 0 1"),
+        Some(GameResult::Timeout {
+            winner: Side::White
+        })
+    );
+    // a lone minor piece mates with the help of a piece in the king's way
+    let black_wins = Some(GameResult::Timeout {
+        winner: Side::Black,
+    });
+    assert_eq!(flag_fall("4k3/8/8/8/8/8/8/R3K1n1 w - - 0 1"), black_wins);
+    assert_eq!(flag_fall("4k3/8/8/8/8/8/P7/4K1b1 w - - 0 1"), black_wins);
+    assert_eq!(flag_fall("4k3/8/8/8/8/8/8/B3Kb2 w - - 0 1"), black_wins);
+}
+
+#[test]
+fn flag_fall_draws_against_insufficient_material() {
+    let draw = Some(GameResult::Draw(DrawReason::TimeoutVsInsufficientMaterial));
+    // a queen in the king's way is no help to a lone knight
+    assert_eq!(flag_fall("4k3/8/8/8/8/8/8/Q3K1n1 w - - 0 1"), draw);
+    assert_eq!(flag_fall("4k2r/8/8/8/8/8/3B4/2B1K3 b - - 0 1"), draw);
+    // neither a rook nor a queen can be made to block for bishops
+    assert_eq!(flag_fall("4k3/8/8/8/8/8/8/R2QK1b1 w - - 0 1"), draw);
+}
+
+#[test]
+fn moving_presses_the_clock() {
+    let mut grid = Grid::new64();
+    let mut game = Game::new(&mut grid);
+    game.clock = Some(Clock::new(TimeControl::new(3, 2, Increment::Fischer)));
+    game.tick(secs(5));
+    assert!(game.make_move(&mut grid, &CellId(4, 6), &CellId(4, 4), None));
+    game.tick(secs(1));
+    let clock = game.clock.as_ref().unwrap();
+    assert_eq!(clock.remaining(Side::White), secs(177));
+    assert_eq!(clock.remaining(Side::Black), secs(179));
+}
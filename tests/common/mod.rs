@@ -0,0 +1,33 @@
+// helpers shared by the integration tests, each of which uses only some of them
+#![allow(dead_code)]
+
+use chess::game::Game;
+use chess::grid::{CellId, Grid};
+use chess::pieces::{PieceType, Side};
+use chess::san;
+
+pub fn sq(square: &str) -> CellId {
+    CellId::from_square(square).unwrap()
+}
+
+pub fn piece_at(grid: &Grid, square: &str) -> Option<(Side, PieceType)> {
+    grid.get_cell(&sq(square))
+        .item
+        .map(|piece| (piece.side, piece.piece_type))
+}
+
+// plays moves in coordinate notation, each of which has to be valid
+pub fn play(grid: &mut Grid, game: &mut Game, moves: &[&str]) {
+    for notation in moves {
+        let (from, to, promotion) = san::parse_coordinate(notation).unwrap();
+        assert!(game.make_move(grid, &from, &to, promotion), "{}", notation);
+    }
+}
+
+// the game set up from `fen` with `moves` played on it
+pub fn play_from(fen: &str, moves: &[&str]) -> (Grid, Game) {
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    play(&mut grid, &mut game, moves);
+    (grid, game)
+}
@@ -0,0 +1,55 @@
+use chess::drawing::{Drawings, MarkColor};
+use chess::game::Game;
+use chess::grid::Grid;
+use chess::pgn::PgnTags;
+
+mod common;
+use common::{play, sq};
+
+#[test]
+fn drawing_again_in_the_same_colour_rubs_out() {
+    let mut drawings = Drawings::default();
+    drawings.set_arrow(sq("e2"), sq("e4"), Some(MarkColor::Green));
+    drawings.set_arrow(sq("e2"), sq("e4"), Some(MarkColor::Red));
+    assert_eq!(drawings.arrow(sq("e2"), sq("e4")), Some(MarkColor::Red));
+    assert_eq!(drawings.arrows.len(), 1);
+    drawings.set_arrow(sq("e2"), sq("e4"), None);
+    drawings.set_circle(sq("d5"), Some(MarkColor::Blue));
+    assert_eq!(drawings.arrow(sq("e2"), sq("e4")), None);
+    assert_eq!(drawings.circle(sq("d5")), Some(MarkColor::Blue));
+    assert!(!drawings.is_empty());
+    drawings.clear();
+    assert!(drawings.is_empty());
+}
+
+#[test]
+fn comment_commands() {
+    let mut drawings = Drawings::default();
+    drawings.set_circle(sq("d5"), Some(MarkColor::Red));
+    drawings.set_circle(sq("f7"), Some(MarkColor::Yellow));
+    drawings.set_arrow(sq("g1"), sq("f3"), Some(MarkColor::Green));
+    assert_eq!(drawings.to_commands(), "[%csl Rd5,Yf7][%cal Gg1f3]");
+}
+
+#[test]
+fn drawings_are_exported_with_their_move() {
+    let mut grid = Grid::new64();
+    let mut game = Game::new(&mut grid);
+    game.drawings_mut()
+        .set_arrow(sq("e2"), sq("e4"), Some(MarkColor::Green));
+    play(&mut grid, &mut game, &["e2e4"]);
+    game.drawings_mut()
+        .set_circle(sq("d5"), Some(MarkColor::Red));
+    play(&mut grid, &mut game, &["e7e5", "g1f3"]);
+
+    // a take back keeps them for the move played again
+    game.undo(&mut grid);
+    game.undo(&mut grid);
+    game.undo(&mut grid);
+    game.redo(&mut grid);
+    game.redo(&mut grid);
+
+    let pgn = game.to_pgn(&PgnTags::default());
+    let movetext = pgn.lines().last().unwrap();
+    assert_eq!(movetext, "{[%cal Ge2e4]} 1. e4 {[%csl Rd5]} 1... e5 *");
+}
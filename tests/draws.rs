@@ -0,0 +1,146 @@
+use chess::game::{DrawReason, Game, GameResult, STARTING_FEN};
+use chess::grid::Grid;
+
+mod common;
+use common::play;
+
+const SHUFFLE: [&str; 4] = ["g1f3", "g8f6", "f3g1", "f6g8"];
+
+#[test]
+fn threefold_repetition_can_be_claimed() {
+    let mut grid = Grid::new64();
+    let mut game = Game::new(&mut grid);
+    play(&mut grid, &mut game, &SHUFFLE);
+    assert_eq!(game.repetitions(), 2);
+    assert_eq!(game.claimable_draw(), None);
+    assert!(!game.claim_draw());
+    play(&mut grid, &mut game, &SHUFFLE);
+    assert_eq!(game.repetitions(), 3);
+    assert_eq!(game.result, None);
+    assert_eq!(game.claimable_draw(), Some(DrawReason::ThreefoldRepetition));
+    assert!(game.claim_draw());
+    assert_eq!(
+        game.result,
+        Some(GameResult::Draw(DrawReason::ThreefoldRepetition))
+    );
+}
+
+#[test]
+fn fivefold_repetition_ends_the_game() {
+    let mut grid = Grid::new64();
+    let mut game = Game::new(&mut grid);
+    for _ in 0..3 {
+        play(&mut grid, &mut game, &SHUFFLE);
+    }
+    assert_eq!(game.result, None);
+    play(&mut grid, &mut game, &SHUFFLE);
+    assert_eq!(
+        game.result,
+        Some(GameResult::Draw(DrawReason::FivefoldRepetition))
+    );
+    // taking the last move back takes the draw back too
+    game.undo(&mut grid);
+    assert_eq!(game.result, None);
+    assert_eq!(game.repetitions(), 4);
+}
+
+#[test]
+fn castling_rights_make_a_different_position() {
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, "r3k3/8/8/8/8/8/8/4K2R w Kq - 0 1").unwrap();
+    play(
+        &mut grid,
+        &mut game,
+        &[
+            "h1h2", "e8d8", "h2h1", "d8e8", "h1h2", "e8d8", "h2h1", "d8e8",
+        ],
+    );
+    // the start, with its castling rights, never came back
+    assert_eq!(game.repetitions(), 2);
+}
+
+#[test]
+fn en_passant_counts_only_when_it_can_be_played() {
+    let mut grid = Grid::new64();
+    let mut game = Game::new(&mut grid);
+    // no black pawn can take on e3, so the position after e4 is the one that comes back
+    play(
+        &mut grid,
+        &mut game,
+        &[
+            "e2e4", "g8f6", "g1f3", "f6g8", "f3g1", "g8f6", "g1f3", "f6g8", "f3g1",
+        ],
+    );
+    assert_eq!(game.repetitions(), 3);
+
+    let fen = "4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1";
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    // here d4xe3 is possible right after e4, and never again
+    play(
+        &mut grid,
+        &mut game,
+        &["e2e4", "e8d8", "e1d1", "d8e8", "d1e1"],
+    );
+    assert_eq!(game.repetitions(), 1);
+}
+
+#[test]
+fn fifty_moves_can_be_claimed_and_seventy_five_end_the_game() {
+    let mut grid = Grid::new64();
+    let fen = "4k3/8/8/8/8/8/4P3/R3K3 w - - 99 80";
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    assert_eq!(game.claimable_draw(), None);
+    play(&mut grid, &mut game, &["a1a2"]);
+    assert_eq!(game.claimable_draw(), Some(DrawReason::FiftyMoves));
+    // a pawn move starts the count over
+    play(&mut grid, &mut game, &["e8d8", "e2e4"]);
+    assert_eq!(game.claimable_draw(), None);
+
+    let fen = "4k3/8/8/8/8/8/4P3/R3K3 w - - 149 100";
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    play(&mut grid, &mut game, &["a1a2"]);
+    assert_eq!(
+        game.result,
+        Some(GameResult::Draw(DrawReason::SeventyFiveMoves))
+    );
+}
+
+#[test]
+fn checkmate_on_the_seventy_fifth_move_stands() {
+    let mut grid = Grid::new64();
+    let fen = "k7/8/1K6/8/8/8/8/7R w - - 149 100";
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    play(&mut grid, &mut game, &["h1h8"]);
+    assert!(matches!(game.result, Some(GameResult::Checkmate { .. })));
+}
+
+fn dead(fen: &str) -> bool {
+    let mut grid = Grid::new64();
+    let game = Game::from_fen(&mut grid, fen).unwrap();
+    game.result == Some(GameResult::Draw(DrawReason::InsufficientMaterial))
+}
+
+#[test]
+fn insufficient_material() {
+    assert!(dead("4k3/8/8/8/8/8/8/4K3 w - - 0 1"));
+    assert!(dead("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+    assert!(dead("4k3/8/8/8/8/8/8/4K1n1 w - - 0 1"));
+    // bishops on dark squares only, whoever they belong to
+    assert!(dead("4kb2/8/8/8/8/8/3B4/2B1K3 w - - 0 1"));
+    assert!(!dead("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1"));
+    assert!(!dead("4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1"));
+    assert!(!dead("4kn2/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+    assert!(!dead("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"));
+    assert!(!dead(STARTING_FEN));
+}
+
+#[test]
+fn capturing_the_last_piece_ends_the_game() {
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, "4k3/8/8/8/8/8/3r4/4K3 w - - 0 1").unwrap();
+    play(&mut grid, &mut game, &["e1d2"]);
+    assert_eq!(
+        game.result,
+        Some(GameResult::Draw(DrawReason::InsufficientMaterial))
+    );
+}
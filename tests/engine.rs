@@ -0,0 +1,168 @@
+use std::time::{Duration, Instant};
+
+use chess::engine::{Engine, EngineError, Reply};
+use chess::game::Game;
+use chess::grid::Grid;
+
+mod common;
+use common::sq;
+
+fn answer(engine: &mut Engine) -> Reply {
+    let started = Instant::now();
+    loop {
+        match engine.poll().unwrap() {
+            Reply::Thinking => {
+                assert!(started.elapsed() < Duration::from_secs(10), "no answer");
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            reply => return reply,
+        }
+    }
+}
+
+// through the handshake, which `spawn` leaves to `poll`
+fn start(program: &str, args: &[&str]) -> Engine {
+    let mut engine = Engine::spawn(program, args).unwrap();
+    let started = Instant::now();
+    loop {
+        match engine.poll().unwrap() {
+            Reply::Starting => {
+                assert!(started.elapsed() < Duration::from_secs(10), "not ready");
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            Reply::Ready => return engine,
+            reply => panic!("{:?} before the handshake was done", reply),
+        }
+    }
+}
+
+// what the stand-in was told, once it has read `quit`; dropping the engine
+// doesn't wait for that
+#[cfg(unix)]
+fn log_after_quit(log: &str) -> String {
+    let started = Instant::now();
+    loop {
+        let text = std::fs::read_to_string(log).unwrap();
+        if text.ends_with("quit\n") || started.elapsed() > Duration::from_secs(10) {
+            return text;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+// a stand-in engine that logs what it is told and answers with the moves it is given, in turn
+#[cfg(unix)]
+fn stand_in(name: &str, moves: &str) -> (String, String) {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let script = dir.join(format!("{}.sh", name));
+    let log = dir.join(format!("{}.log", name));
+    std::fs::write(
+        &script,
+        format!(
+            r#"#!/bin/sh
+set -- {moves}
+: > "{log}"
+while read -r line; do
+    echo "$line" >> "{log}"
+    case "$line" in
+        uci) echo "id name Stand-in"; echo "option name Hash type spin"; echo "uciok";;
+        isready) echo "readyok";;
+        go*) echo "info depth 1"; echo "bestmove ${{1:-0000}}"; [ $# -gt 0 ] && shift;;
+        quit) exit 0;;
+    esac
+done
+"#,
+            log = log.display(),
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    (script.display().to_string(), log.display().to_string())
+}
+
+#[cfg(unix)]
+#[test]
+fn plays_the_stand_in_moves() {
+    let (script, log) = stand_in("replies", "e7e5 b8c6");
+    let mut engine = start(&script, &[]);
+    assert_eq!(engine.name, "Stand-in");
+
+    let mut grid = Grid::new64();
+    let mut game = Game::new(&mut grid);
+    for (played, reply) in [("e2e4", ("e7", "e5")), ("g1f3", ("b8", "c6"))] {
+        let (from, to) = (sq(&played[..2]), sq(&played[2..]));
+        assert!(game.make_move(&mut grid, &from, &to, None));
+        engine.go(&game, Duration::from_millis(10)).unwrap();
+        assert_eq!(
+            answer(&mut engine),
+            Reply::BestMove(sq(reply.0), sq(reply.1), None)
+        );
+        assert!(game.make_move(&mut grid, &sq(reply.0), &sq(reply.1), None));
+    }
+    assert_eq!(engine.poll().unwrap(), Reply::Thinking);
+    // out of moves, the stand-in has nothing to play
+    engine.go(&game, Duration::from_millis(10)).unwrap();
+    assert_eq!(answer(&mut engine), Reply::NoMove);
+    drop(engine);
+
+    let log = log_after_quit(&log);
+    assert!(log.contains("position startpos moves e2e4\ngo movetime 10\n"));
+    assert!(log.contains("position startpos moves e2e4 e7e5 g1f3\n"));
+    assert!(log.ends_with("quit\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn throws_away_answers_to_called_off_searches() {
+    let (script, _) = stand_in("stale", "a7a6 h7h6");
+    let mut engine = start(&script, &[]);
+    let mut grid = Grid::new64();
+    let mut game = Game::new(&mut grid);
+    assert!(game.make_move(&mut grid, &sq("e2"), &sq("e4"), None));
+    engine.go(&game, Duration::from_millis(10)).unwrap();
+    // asked again before the first answer was read, as after an undo
+    engine.go(&game, Duration::from_millis(10)).unwrap();
+    assert_eq!(
+        answer(&mut engine),
+        Reply::BestMove(sq("h7"), sq("h6"), None)
+    );
+}
+
+#[test]
+fn plays_against_itself_over_uci() {
+    let mut engine = start(env!("CARGO_BIN_EXE_chess-uci"), &[]);
+    assert_eq!(engine.name, "chess");
+    let mut grid = Grid::new64();
+    let game = Game::from_fen(&mut grid, "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    engine.go(&game, Duration::from_millis(200)).unwrap();
+    assert_eq!(
+        answer(&mut engine),
+        Reply::BestMove(sq("a1"), sq("a8"), None)
+    );
+}
+
+// an engine that never answers holds up neither starting it nor dropping it
+#[cfg(unix)]
+#[test]
+fn a_silent_engine_does_not_block() {
+    let started = Instant::now();
+    let mut engine = Engine::spawn("sh", &["-c", "sleep 5"]).unwrap();
+    assert_eq!(engine.poll().unwrap(), Reply::Starting);
+    let mut grid = Grid::new64();
+    let game = Game::new(&mut grid);
+    assert!(matches!(
+        engine.go(&game, Duration::from_millis(10)),
+        Err(EngineError::NotReady)
+    ));
+    drop(engine);
+    assert!(started.elapsed() < Duration::from_millis(400));
+}
+
+#[test]
+fn missing_program() {
+    assert!(matches!(
+        Engine::spawn("./no-such-engine", &[]),
+        Err(EngineError::Io(_))
+    ));
+}
@@ -0,0 +1,122 @@
+use chess::fen::FenError;
+use chess::game::Game;
+use chess::grid::Grid;
+use chess::pieces::Side;
+
+mod common;
+use common::play;
+
+fn load(fen: &str) -> Result<Game, FenError> {
+    Game::from_fen(&mut Grid::new64(), fen)
+}
+
+fn round_trip(fen: &str) -> String {
+    let mut grid = Grid::new64();
+    let game = Game::from_fen(&mut grid, fen).unwrap();
+    game.to_fen()
+}
+
+#[test]
+fn fens_come_back_as_they_went_in() {
+    for fen in [
+        chess::game::STARTING_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/8/8/8/8/8/8/R3K2R b Kq - 7 42",
+        // en passant on the very first move of a game set up from a FEN
+        "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1",
+        "rnbqkbnr/pppp1ppp/8/8/3Pp3/8/PPP1PPPP/RNBQKBNR b KQkq d3 0 2",
+    ] {
+        assert_eq!(round_trip(fen), fen);
+    }
+}
+
+#[test]
+fn played_moves_show_in_the_fen() {
+    let mut grid = Grid::new64();
+    let mut game = Game::new(&mut grid);
+    play(&mut grid, &mut game, &["e2e4", "c7c5", "g1f3"]);
+    assert_eq!(
+        game.to_fen(),
+        "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+    );
+}
+
+#[test]
+fn counters_default_when_left_out() {
+    assert_eq!(
+        round_trip("4k3/8/8/8/8/8/8/4K2R w K -"),
+        "4k3/8/8/8/8/8/8/4K2R w K - 0 1"
+    );
+}
+
+#[test]
+fn errors() {
+    let cases: [(&str, FenError); 12] = [
+        ("", FenError::MissingField("piece placement")),
+        (
+            "4k3/8/8/8/8/8/8/4K3",
+            FenError::MissingField("side to move"),
+        ),
+        ("4k3/8/8/8/8/8/8/4K3 w", FenError::MissingField("castling")),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w -",
+            FenError::MissingField("en passant"),
+        ),
+        ("4k3/8/8/8/8/8/8/4K3 w - - 0 1 x", FenError::TooManyFields),
+        ("4k3/8/8/8/8/8/4K3 w - - 0 1", FenError::RankCount(7)),
+        (
+            "4k3/8/8/8/8/8/8/4K4 w - - 0 1",
+            FenError::RankLength {
+                rank: 1,
+                squares: 9,
+            },
+        ),
+        ("4k3/8/8/8/8/8/8/4X3 w - - 0 1", FenError::InvalidPiece('X')),
+        (
+            "8/8/8/8/8/8/8/4K3 w - - 0 1",
+            FenError::KingCount {
+                side: Side::Black,
+                count: 0,
+            },
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 x - - 0 1",
+            FenError::InvalidSide("x".to_string()),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+            FenError::InvalidCastling("K".to_string()),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - e3 0 1",
+            FenError::InvalidEnPassant("e3".to_string()),
+        ),
+    ];
+    for (fen, err) in cases {
+        assert_eq!(load(fen).err(), Some(err), "{}", fen);
+    }
+    assert_eq!(
+        load("4k3/8/8/8/8/8/8/4K3 w - - x 1").err(),
+        Some(FenError::InvalidCounter {
+            field: "halfmove clock",
+            value: "x".to_string(),
+        })
+    );
+    assert_eq!(
+        load("4k3/8/8/8/8/8/8/4K3 w - - 0 4294967295").err(),
+        Some(FenError::InvalidCounter {
+            field: "fullmove number",
+            value: "4294967295".to_string(),
+        })
+    );
+}
+
+#[test]
+fn the_side_not_to_move_may_not_be_in_check() {
+    assert_eq!(
+        load("4k3/4R3/8/8/8/8/8/4K3 w - - 0 1").err(),
+        Some(FenError::OpponentInCheck(Side::Black))
+    );
+    // the same with Black to move is just check
+    assert!(load("4k3/4R3/8/8/8/8/8/4K3 b - - 0 1").is_ok());
+}
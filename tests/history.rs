@@ -0,0 +1,128 @@
+use chess::game::Game;
+use chess::grid::Grid;
+use chess::pieces::{PieceType, Side};
+
+mod common;
+use common::{piece_at, play, sq};
+
+#[test]
+fn at_ply_plays_the_game_out_again_up_to_the_ply() {
+    let mut grid = Grid::new64();
+    let fen = "r3k3/6P1/8/8/8/8/8/4K2R w Kq - 0 1";
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    play(&mut grid, &mut game, &["e1g1", "e8c8", "g7g8q"]);
+    let live = game.to_fen();
+
+    let mut past = Grid::new64();
+    assert_eq!(game.at_ply(&mut past, 0).to_fen(), fen);
+    assert_eq!(
+        game.at_ply(&mut past, 2).to_fen(),
+        "2kr4/6P1/8/8/8/8/8/5RK1 w - - 2 2"
+    );
+    assert_eq!(game.at_ply(&mut past, 3).to_fen(), live);
+    // the game looked back on is left as it is
+    assert_eq!(game.to_fen(), live);
+    assert_eq!(game.history.len(), 3);
+}
+
+#[test]
+fn undo_puts_an_en_passant_pawn_back() {
+    let mut grid = Grid::new64();
+    let fen = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1";
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    play(&mut grid, &mut game, &["e5d6"]);
+    assert_eq!(piece_at(&grid, "d5"), None);
+    assert_eq!(game.black_stack.len(), 1);
+
+    assert!(game.undo(&mut grid));
+    assert_eq!(piece_at(&grid, "d5"), Some((Side::Black, PieceType::Pawn)));
+    assert_eq!(piece_at(&grid, "e5"), Some((Side::White, PieceType::Pawn)));
+    assert_eq!(piece_at(&grid, "d6"), None);
+    assert!(game.black_stack.is_empty());
+    // the capture is still there to be made
+    assert_eq!(game.to_fen(), fen);
+    play(&mut grid, &mut game, &["e5d6"]);
+}
+
+#[test]
+fn undo_takes_back_a_promotion() {
+    let mut grid = Grid::new64();
+    let fen = "1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1";
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    play(&mut grid, &mut game, &["a7b8q"]);
+    assert_eq!(piece_at(&grid, "b8"), Some((Side::White, PieceType::Queen)));
+    assert!(game.undo(&mut grid));
+    assert_eq!(piece_at(&grid, "a7"), Some((Side::White, PieceType::Pawn)));
+    assert_eq!(
+        piece_at(&grid, "b8"),
+        Some((Side::Black, PieceType::Knight))
+    );
+    assert!(game.black_stack.is_empty());
+    assert_eq!(game.to_fen(), fen);
+
+    // one still waiting for its piece
+    assert!(game.play(&mut grid, &sq("a7"), &sq("a8")));
+    assert!(game.promotion.is_some());
+    assert_eq!(piece_at(&grid, "a8"), Some((Side::White, PieceType::Pawn)));
+    assert!(game.undo(&mut grid));
+    assert!(game.promotion.is_none());
+    assert!(game.history.is_empty());
+    assert_eq!(piece_at(&grid, "a7"), Some((Side::White, PieceType::Pawn)));
+    assert_eq!(piece_at(&grid, "a8"), None);
+    assert_eq!(game.turn(), Side::White);
+    assert_eq!(game.move_count, 0);
+    assert_eq!(game.to_fen(), fen);
+
+    // and played again, the picker comes back
+    assert!(game.redo(&mut grid));
+    assert!(game.redo_stack.is_empty());
+    assert_eq!(game.promotion.as_ref().unwrap().cell, sq("a8"));
+    assert_eq!(piece_at(&grid, "a8"), Some((Side::White, PieceType::Pawn)));
+    assert!(game.promote(&mut grid, PieceType::Knight));
+    assert_eq!(game.to_fen(), "Nn2k3/8/8/8/8/8/8/4K3 b - - 0 1");
+}
+
+#[test]
+fn undo_puts_a_castled_rook_back() {
+    let mut grid = Grid::new64();
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    play(&mut grid, &mut game, &["e1g1", "e8c8"]);
+    assert!(game.undo(&mut grid));
+    assert_eq!(piece_at(&grid, "a8"), Some((Side::Black, PieceType::Rook)));
+    assert_eq!(piece_at(&grid, "d8"), None);
+    assert!(game.undo(&mut grid));
+    assert_eq!(piece_at(&grid, "h1"), Some((Side::White, PieceType::Rook)));
+    assert_eq!(piece_at(&grid, "e1"), Some((Side::White, PieceType::King)));
+    assert_eq!(piece_at(&grid, "f1"), None);
+    assert_eq!(piece_at(&grid, "g1"), None);
+    // castling rights come back with the rook
+    assert_eq!(game.to_fen(), fen);
+    assert!(!game.undo(&mut grid));
+}
+
+#[test]
+fn a_new_move_clears_the_redo_stack() {
+    let mut grid = Grid::new64();
+    let mut game = Game::new(&mut grid);
+    play(&mut grid, &mut game, &["e2e4", "e7e5", "g1f3"]);
+    let fen = game.to_fen();
+    assert!(game.undo(&mut grid));
+    assert!(game.undo(&mut grid));
+    assert_eq!(game.redo_stack.len(), 2);
+    assert!(game.redo(&mut grid));
+    assert!(game.redo(&mut grid));
+    assert!(!game.redo(&mut grid));
+    assert_eq!(game.to_fen(), fen);
+
+    assert!(game.undo(&mut grid));
+    assert!(game.undo(&mut grid));
+    play(&mut grid, &mut game, &["c7c5"]);
+    assert!(game.redo_stack.is_empty());
+    assert!(!game.redo(&mut grid));
+    assert_eq!(game.history.len(), 2);
+    assert_eq!(
+        game.to_fen(),
+        "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2"
+    );
+}
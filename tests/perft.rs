@@ -0,0 +1,135 @@
+use chess::game::{DrawReason, Game, GameResult};
+use chess::grid::Grid;
+use chess::perft::{divide, perft, perft_position};
+
+fn nodes(fen: &str, depth: u32) -> u64 {
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    let position = game.position;
+    let nodes = perft(&mut grid, &mut game, depth);
+    // walking the tree must leave the position as it found it
+    assert_eq!(game.to_fen(), fen);
+    assert_eq!(game.position, position);
+    // and the bitboards alone must agree with the game
+    assert_eq!(perft_position(&position, depth), nodes);
+    nodes
+}
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+#[test]
+fn initial_position() {
+    let fen = chess::game::STARTING_FEN;
+    assert_eq!(nodes(fen, 1), 20);
+    assert_eq!(nodes(fen, 2), 400);
+    assert_eq!(nodes(fen, 3), 8902);
+}
+
+#[test]
+fn kiwipete() {
+    assert_eq!(nodes(KIWIPETE, 1), 48);
+    assert_eq!(nodes(KIWIPETE, 2), 2039);
+}
+
+#[test]
+fn standard_positions() {
+    assert_eq!(nodes("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 3), 2812);
+    assert_eq!(
+        nodes(
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            3
+        ),
+        9467
+    );
+    assert_eq!(
+        nodes(
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            2
+        ),
+        1486
+    );
+    assert_eq!(
+        nodes(
+            "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+            2
+        ),
+        2079
+    );
+}
+
+#[test]
+fn castling_edge_cases() {
+    // castling out of check, through check and with a moved rook
+    assert_eq!(nodes("r6r/1b2k1bq/8/8/7B/8/8/R3K2R b KQ - 3 2", 1), 8);
+    assert_eq!(
+        nodes(
+            "r3k2r/p1pp1pb1/bn2Qnp1/2qPN3/1p2P3/2N5/PPPBBPPP/R3K2R b KQkq - 3 2",
+            1
+        ),
+        5
+    );
+    assert_eq!(
+        nodes(
+            "2kr3r/p1ppqpb1/bn2Qnp1/3PN3/1p2P3/2N5/PPPBBPPP/R3K2R b KQ - 3 2",
+            1
+        ),
+        44
+    );
+    assert_eq!(nodes("5k2/8/8/8/8/8/8/4K2R w K - 0 1", 4), 6399);
+    assert_eq!(nodes("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", 4), 7418);
+}
+
+#[test]
+fn en_passant_edge_cases() {
+    // the double step may be taken, unless that exposes the king
+    assert_eq!(nodes("8/8/8/2k5/2pP4/8/B7/4K3 b - d3 0 3", 1), 8);
+    assert_eq!(nodes("3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1", 4), 10138);
+    assert_eq!(nodes("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", 4), 13931);
+}
+
+#[test]
+fn promotion_edge_cases() {
+    assert_eq!(nodes("2r5/3pk3/8/2P5/8/2K5/8/8 w - - 5 4", 1), 9);
+    assert_eq!(
+        nodes(
+            "rnb2k1r/pp1Pbppp/2p5/q7/2B5/8/PPPQNnPP/RNB1K2R w KQ - 3 9",
+            1
+        ),
+        39
+    );
+    assert_eq!(nodes("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", 4), 2661);
+    assert_eq!(nodes("8/P1k5/K7/8/8/8/8/8 w - - 0 1", 4), 1329);
+    assert_eq!(nodes("K1k5/8/P7/8/8/8/8/8 w - - 0 1", 6), 2217);
+}
+
+#[test]
+fn divide_splits_by_root_move() {
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, KIWIPETE).unwrap();
+    let split = divide(&mut grid, &mut game, 2);
+    assert_eq!(split.len(), 48);
+    assert_eq!(split.iter().map(|(_, nodes)| nodes).sum::<u64>(), 2039);
+    assert!(split.contains(&("e1g1".to_string(), 43)));
+    assert!(split.contains(&("e1c1".to_string(), 43)));
+
+    let mut game = Game::from_fen(&mut grid, "4k3/1P6/8/8/8/8/K7/8 w - - 0 1").unwrap();
+    let split = divide(&mut grid, &mut game, 1);
+    for promotion in ["b7b8q", "b7b8r", "b7b8b", "b7b8n"] {
+        assert!(split.contains(&(promotion.to_string(), 1)));
+    }
+}
+
+#[test]
+fn a_drawn_root_keeps_its_result() {
+    // bare kings are dead drawn from the start, yet the kings can still move
+    let fen = "4k3/8/8/8/8/8/8/4K3 w - - 0 1";
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    let drawn = Some(GameResult::Draw(DrawReason::InsufficientMaterial));
+    assert_eq!(game.result, drawn);
+    assert_eq!(nodes(fen, 2), 25);
+    assert_eq!(perft(&mut grid, &mut game, 2), 25);
+    assert_eq!(game.result, drawn);
+    assert_eq!(divide(&mut grid, &mut game, 1).len(), 5);
+    assert_eq!(game.result, drawn);
+}
@@ -0,0 +1,178 @@
+use chess::grid::{CellId, Grid};
+use chess::pgn::{parse_pgn, PgnError, PgnMove};
+use chess::pieces::PieceType;
+use chess::san;
+
+mod common;
+use common::{play_from, sq};
+
+fn sans(moves: &[PgnMove]) -> Vec<&str> {
+    moves.iter().map(|played| played.san.as_str()).collect()
+}
+
+fn resolve(fen: &str, notation: &str) -> Option<(CellId, CellId, Option<PieceType>)> {
+    let (grid, game) = play_from(fen, &[]);
+    san::resolve(&grid, &game, notation)
+}
+
+#[test]
+fn tags_comments_and_nags() {
+    let text = "[Event \"Casual \\\"game\\\"\"]\n\
+                [White \"Anderssen\"]\n\
+                % an escaped line 1. d4\n\
+                {before any move} 1. e4 {best by test} e5 $1 2.Nf3!? ; to the end of the line (\n\
+                2...Nc6 3. Bb5?? 1-0\n";
+    let games = parse_pgn(text).unwrap();
+    assert_eq!(games.len(), 1);
+    let game = &games[0];
+    assert_eq!(
+        game.tags,
+        [
+            ("Event".to_string(), "Casual \"game\"".to_string()),
+            ("White".to_string(), "Anderssen".to_string()),
+        ]
+    );
+    assert_eq!(game.tag("White"), Some("Anderssen"));
+    assert_eq!(game.tag("Black"), None);
+    assert_eq!(sans(&game.moves), ["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+    assert_eq!(game.moves[0].comments, ["before any move", "best by test"]);
+    assert_eq!(game.moves[1].nags, [1]);
+    assert_eq!(game.moves[2].nags, [5]);
+    assert_eq!(game.moves[2].comments, ["to the end of the line ("]);
+    assert_eq!(game.moves[4].nags, [4]);
+    assert_eq!(game.result, "1-0");
+}
+
+#[test]
+fn nested_variations() {
+    let text = "1. e4 e5 (1... c5 2. Nf3 (2. c3 d5) d6) (1... e6) 2. Nf3 *";
+    let game = &parse_pgn(text).unwrap()[0];
+    assert_eq!(sans(&game.moves), ["e4", "e5", "Nf3"]);
+    let variations = &game.moves[1].variations;
+    assert_eq!(variations.len(), 2);
+    assert_eq!(sans(&variations[0]), ["c5", "Nf3", "d6"]);
+    assert_eq!(sans(&variations[0][1].variations[0]), ["c3", "d5"]);
+    assert_eq!(sans(&variations[1]), ["e6"]);
+    assert_eq!(game.result, "*");
+}
+
+#[test]
+fn several_games_in_one_file() {
+    let text = "[Event \"first\"]\n\n1. d4 d5 1/2-1/2\n\n\
+                [Event \"second\"]\n[Result \"0-1\"]\n\n1. f3 e5 2. g4 Qh4#\n";
+    let games = parse_pgn(text).unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].tag("Event"), Some("first"));
+    assert_eq!(games[0].result, "1/2-1/2");
+    assert_eq!(games[1].tag("Event"), Some("second"));
+    assert_eq!(sans(&games[1].moves), ["f3", "e5", "g4", "Qh4#"]);
+    // no result at the end of the movetext, the tag has it
+    assert_eq!(games[1].result, "0-1");
+
+    let mut grid = Grid::new64();
+    let game = games[1].to_game(&mut grid, 4).unwrap();
+    assert_eq!(game.history.len(), 4);
+    assert!(game.result.is_some());
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        parse_pgn("[Event \"x").err(),
+        Some(PgnError::UnterminatedTag)
+    );
+    assert_eq!(
+        parse_pgn("[Event x]").err(),
+        Some(PgnError::UnterminatedTag)
+    );
+    assert_eq!(
+        parse_pgn("1. e4 {no end").err(),
+        Some(PgnError::UnterminatedComment)
+    );
+    assert_eq!(
+        parse_pgn("1. e4 (1. d4 d5").err(),
+        Some(PgnError::UnbalancedVariation)
+    );
+    assert_eq!(
+        parse_pgn("1. e4 e5) *").err(),
+        Some(PgnError::UnbalancedVariation)
+    );
+    assert_eq!(parse_pgn(" \n").err(), Some(PgnError::NoGame));
+
+    let game = &parse_pgn("1. e4 e5 2. Ke3 *").unwrap()[0];
+    let mut grid = Grid::new64();
+    assert_eq!(
+        game.to_game(&mut grid, 3).err(),
+        Some(PgnError::IllegalMove {
+            index: 2,
+            san: "Ke3".to_string()
+        })
+    );
+    // only the plies asked for are played
+    assert!(game.to_game(&mut grid, 2).is_ok());
+}
+
+#[test]
+fn san_disambiguation() {
+    // knights on b1 and f1 both reach d2
+    let knights = "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1";
+    assert_eq!(resolve(knights, "Nbd2"), Some((sq("b1"), sq("d2"), None)));
+    assert_eq!(resolve(knights, "Nfd2"), Some((sq("f1"), sq("d2"), None)));
+    assert_eq!(resolve(knights, "Nd2"), None);
+    // only the b1 knight reaches a3
+    assert_eq!(resolve(knights, "Na3"), Some((sq("b1"), sq("a3"), None)));
+
+    // rooks on a1 and a5 both reach a3
+    let rooks = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+    assert_eq!(resolve(rooks, "R1a3"), Some((sq("a1"), sq("a3"), None)));
+    assert_eq!(resolve(rooks, "R5xa3"), Some((sq("a5"), sq("a3"), None)));
+    assert_eq!(resolve(rooks, "Ra3"), None);
+
+    // queens on a1, a3 and c1 all reach b2, a1 needs its full square
+    let queens = "8/8/8/7k/8/Q7/4K3/Q1Q5 w - - 0 1";
+    assert_eq!(resolve(queens, "Qa1b2"), Some((sq("a1"), sq("b2"), None)));
+    assert_eq!(resolve(queens, "Qab2"), None);
+    assert_eq!(resolve(queens, "Q1b2"), None);
+    assert_eq!(resolve(queens, "Qcb2"), Some((sq("c1"), sq("b2"), None)));
+}
+
+#[test]
+fn san_promotion() {
+    let fen = "1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1";
+    let queen = Some((sq("a7"), sq("a8"), Some(PieceType::Queen)));
+    assert_eq!(resolve(fen, "a8=Q"), queen);
+    assert_eq!(resolve(fen, "a8Q"), queen);
+    assert_eq!(resolve(fen, "a8=Q+"), queen);
+    assert_eq!(
+        resolve(fen, "axb8=N"),
+        Some((sq("a7"), sq("b8"), Some(PieceType::Knight)))
+    );
+    // a pawn reaching the last rank has to say what it becomes
+    assert_eq!(resolve(fen, "a8"), None);
+    assert_eq!(resolve(fen, "a8=K"), None);
+}
+
+#[test]
+fn san_castling() {
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    let kingside = Some((sq("e1"), sq("g1"), None));
+    let queenside = Some((sq("e1"), sq("c1"), None));
+    assert_eq!(resolve(fen, "O-O"), kingside);
+    assert_eq!(resolve(fen, "0-0"), kingside);
+    assert_eq!(resolve(fen, "O-O-O"), queenside);
+    assert_eq!(resolve(fen, "0-0-0+"), queenside);
+    // no right left to castle
+    assert_eq!(resolve("r3k2r/8/8/8/8/8/8/R3K2R w Qkq - 0 1", "O-O"), None);
+}
+
+#[test]
+fn san_that_fits_no_move() {
+    let fen = chess::game::STARTING_FEN;
+    assert_eq!(resolve(fen, "Nf3"), Some((sq("g1"), sq("f3"), None)));
+    assert_eq!(resolve(fen, "e5"), None);
+    assert_eq!(resolve(fen, "Ke2"), None);
+    assert_eq!(resolve(fen, "Nd4"), None);
+    assert_eq!(resolve(fen, "Zf3"), None);
+    assert_eq!(resolve(fen, "f"), None);
+    assert_eq!(resolve(fen, "O-O"), None);
+}
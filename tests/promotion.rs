@@ -0,0 +1,81 @@
+use chess::game::Game;
+use chess::grid::Grid;
+use chess::pieces::{PieceType, Side};
+
+mod common;
+use common::{piece_at, sq};
+
+#[test]
+fn the_pawn_waits_for_its_piece() {
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, "4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert!(game.play(&mut grid, &sq("a7"), &sq("a8")));
+    let promotion = game.promotion.as_ref().unwrap();
+    assert_eq!(promotion.cell, sq("a8"));
+    assert_eq!(promotion.side, Side::White);
+    assert_eq!(piece_at(&grid, "a8"), Some((Side::White, PieceType::Pawn)));
+    // the turn is not over yet, and nothing else may move
+    assert_eq!(game.turn(), Side::White);
+    assert!(!game.play(&mut grid, &sq("e1"), &sq("d1")));
+    assert!(!game.play(&mut grid, &sq("e8"), &sq("d8")));
+
+    assert!(!game.promote(&mut grid, PieceType::King));
+    assert!(!game.promote(&mut grid, PieceType::Pawn));
+    assert!(game.promote(&mut grid, PieceType::Rook));
+    assert!(game.promotion.is_none());
+    assert_eq!(piece_at(&grid, "a8"), Some((Side::White, PieceType::Rook)));
+    assert_eq!(game.turn(), Side::Black);
+    assert!(game.checked.is_some());
+    assert_eq!(game.history[0].san, "a8=R+");
+    assert_eq!(game.to_fen(), "R3k3/8/8/8/8/8/8/4K3 b - - 0 1");
+    // nothing left to pick
+    assert!(!game.promote(&mut grid, PieceType::Queen));
+}
+
+#[test]
+fn black_promotes_with_a_capture() {
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, "4k3/8/8/8/8/8/6p1/4K2R b - - 0 1").unwrap();
+    assert!(game.make_move(&mut grid, &sq("g2"), &sq("h1"), Some(PieceType::Knight)));
+    assert_eq!(
+        piece_at(&grid, "h1"),
+        Some((Side::Black, PieceType::Knight))
+    );
+    assert_eq!(game.white_stack.len(), 1);
+    assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/8/4K2n w - - 0 2");
+}
+
+#[test]
+fn the_picker_runs_from_the_back_rank() {
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, "4k3/8/8/8/8/8/1p6/4K3 b - - 0 1").unwrap();
+    assert!(game.play(&mut grid, &sq("b2"), &sq("b1")));
+    let promotion = game.promotion.as_ref().unwrap();
+    assert_eq!(
+        promotion.choices(),
+        [
+            (sq("b1"), PieceType::Queen),
+            (sq("b2"), PieceType::Rook),
+            (sq("b3"), PieceType::Bishop),
+            (sq("b4"), PieceType::Knight),
+        ]
+    );
+    assert_eq!(promotion.choice_at(&sq("b3")), Some(PieceType::Bishop));
+    assert_eq!(promotion.choice_at(&sq("b5")), None);
+    assert_eq!(promotion.choice_at(&sq("a1")), None);
+}
+
+#[test]
+fn a_move_without_a_piece_is_taken_back() {
+    let mut grid = Grid::new64();
+    let fen = "4k3/P7/8/8/8/8/8/4K3 w - - 0 1";
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    assert!(!game.make_move(&mut grid, &sq("a7"), &sq("a8"), None));
+    assert!(!game.make_move(&mut grid, &sq("a7"), &sq("a8"), Some(PieceType::King)));
+    assert!(game.promotion.is_none());
+    assert!(game.history.is_empty());
+    assert!(game.redo_stack.is_empty());
+    assert_eq!(piece_at(&grid, "a7"), Some((Side::White, PieceType::Pawn)));
+    assert_eq!(game.to_fen(), fen);
+    assert!(game.make_move(&mut grid, &sq("a7"), &sq("a8"), Some(PieceType::Queen)));
+}
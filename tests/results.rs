@@ -0,0 +1,72 @@
+use chess::game::{Game, GameResult};
+use chess::pieces::Side;
+use chess::san;
+
+mod common;
+use common::play_from;
+
+#[test]
+fn checkmate() {
+    let (mut grid, mut game) =
+        play_from(chess::game::STARTING_FEN, &["f2f3", "e7e5", "g2g4", "d8h4"]);
+    assert_eq!(
+        game.result,
+        Some(GameResult::Checkmate {
+            winner: Side::Black
+        })
+    );
+    assert_eq!(game.result.as_ref().unwrap().winner(), Some(Side::Black));
+    assert!(game.checked.is_some());
+    assert!(game.all_valid_moves().is_empty());
+    // the game is over
+    let (from, to, _) = san::parse_coordinate("g8f6").unwrap();
+    assert!(!game.play(&mut grid, &from, &to));
+
+    // taking the mate back opens the game again
+    assert!(game.undo(&mut grid));
+    assert_eq!(game.result, None);
+    assert!(game.play(&mut grid, &from, &to));
+}
+
+#[test]
+fn check_that_can_be_answered_is_not_mate() {
+    // the checking rook can be taken by the bishop
+    let (_, game) = play_from("6k1/5ppp/8/8/4b3/8/8/R3K3 w - - 0 1", &["a1a8"]);
+    assert!(game.checked.is_some());
+    assert_eq!(game.result, None);
+    // a back rank mate
+    let (_, game) = play_from("6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1", &["a1a8"]);
+    assert_eq!(
+        game.result,
+        Some(GameResult::Checkmate {
+            winner: Side::White
+        })
+    );
+}
+
+#[test]
+fn double_check() {
+    let (_, game) = play_from("k3r3/8/8/4n3/8/7R/8/4K3 b - - 0 1", &["e5d3"]);
+    let check = game.checked.as_ref().unwrap();
+    assert!(check.absolute);
+    // the rook could take the knight, but only the king may move
+    assert_eq!(game.result, None);
+    assert!(game
+        .all_valid_moves()
+        .iter()
+        .all(|(from, _, _)| from.to_square() == "e1"));
+}
+
+#[test]
+fn stalemate() {
+    let (mut grid, mut game) = play_from("7k/8/4Q1K1/8/8/8/8/8 w - - 0 1", &["e6f7"]);
+    assert_eq!(game.result, Some(GameResult::Stalemate));
+    assert_eq!(game.result.as_ref().unwrap().winner(), None);
+    assert!(game.checked.is_none());
+    assert!(game.undo(&mut grid));
+    assert_eq!(game.result, None);
+
+    // a position set up with no move to play is over from the start
+    let game = Game::from_fen(&mut grid, "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    assert_eq!(game.result, Some(GameResult::Stalemate));
+}
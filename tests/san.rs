@@ -0,0 +1,124 @@
+use chess::game::{Game, STARTING_FEN};
+use chess::grid::Grid;
+use chess::pgn::PgnTags;
+
+mod common;
+use common::{play_from, sq};
+
+fn sans(fen: &str, moves: &[&str]) -> Vec<String> {
+    let (_, game) = play_from(fen, moves);
+    game.history
+        .iter()
+        .map(|played| played.san.clone())
+        .collect()
+}
+
+#[test]
+fn disambiguation() {
+    // knights on b1 and f1 both reach d2, only b1 reaches a3
+    let knights = "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1";
+    assert_eq!(sans(knights, &["b1d2"]), ["Nbd2"]);
+    assert_eq!(sans(knights, &["f1d2"]), ["Nfd2"]);
+    assert_eq!(sans(knights, &["b1a3"]), ["Na3"]);
+    // rooks on a1 and a5 both reach a3
+    let rooks = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+    assert_eq!(sans(rooks, &["a1a3"]), ["R1a3"]);
+    assert_eq!(sans(rooks, &["a5a3"]), ["R5a3"]);
+    // queens on a1, a3 and c1 all reach b2
+    let queens = "8/8/8/7k/8/Q7/4K3/Q1Q5 w - - 0 1";
+    assert_eq!(sans(queens, &["a1b2"]), ["Qa1b2"]);
+    assert_eq!(sans(queens, &["a3b2"]), ["Q3b2"]);
+    assert_eq!(sans(queens, &["c1b2"]), ["Qcb2"]);
+    // a pinned knight is no rival
+    let pinned = "4k3/4r3/8/8/8/8/4N3/1N2K3 w - - 0 1";
+    assert_eq!(sans(pinned, &["b1c3"]), ["Nc3"]);
+}
+
+#[test]
+fn captures_and_castling() {
+    assert_eq!(
+        sans(STARTING_FEN, &["e2e4", "d7d5", "e4d5", "d8d5", "b1c3"]),
+        ["e4", "d5", "exd5", "Qxd5", "Nc3"]
+    );
+    assert_eq!(
+        sans("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", &["e5d6"]),
+        ["exd6"]
+    );
+    assert_eq!(
+        sans("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", &["e1g1", "e8c8"]),
+        ["O-O", "O-O-O"]
+    );
+}
+
+#[test]
+fn check_and_mate_suffixes() {
+    assert_eq!(sans("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", &["a1a8"]), ["Ra8+"]);
+    assert_eq!(
+        sans(STARTING_FEN, &["f2f3", "e7e5", "g2g4", "d8h4"]),
+        ["f3", "e5", "g4", "Qh4#"]
+    );
+    // castling can give check too
+    assert_eq!(sans("5k2/8/8/8/8/8/8/4K2R w K - 0 1", &["e1g1"]), ["O-O+"]);
+}
+
+#[test]
+fn promotion() {
+    let fen = "1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1";
+    assert_eq!(sans(fen, &["a7a8q"]), ["a8=Q"]);
+    assert_eq!(sans(fen, &["a7a8n"]), ["a8=N"]);
+    assert_eq!(sans(fen, &["a7b8q"]), ["axb8=Q+"]);
+
+    // the move is written without its piece while the choice is pending
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    assert!(game.play(&mut grid, &sq("a7"), &sq("b8")));
+    assert_eq!(game.history[0].san, "axb8");
+}
+
+#[test]
+fn seven_tag_roster() {
+    let (_, game) = play_from(STARTING_FEN, &["f2f3", "e7e5", "g2g4", "d8h4"]);
+    let tags = PgnTags {
+        event: "Club \"blitz\"".to_string(),
+        white: "Fool".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        game.to_pgn(&tags),
+        "[Event \"Club \\\"blitz\\\"\"]\n\
+         [Site \"?\"]\n\
+         [Date \"????.??.??\"]\n\
+         [Round \"?\"]\n\
+         [White \"Fool\"]\n\
+         [Black \"?\"]\n\
+         [Result \"0-1\"]\n\
+         \n\
+         1. f3 e5 2. g4 Qh4# 0-1\n"
+    );
+}
+
+#[test]
+fn set_up_positions_and_long_games() {
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 12";
+    let (_, game) = play_from(fen, &["e8g8", "e1c1"]);
+    let pgn = game.to_pgn(&PgnTags::default());
+    assert!(pgn.contains(
+        "[Result \"*\"]\n[SetUp \"1\"]\n[FEN \"r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 12\"]\n\n"
+    ));
+    // Black's first move carries its own number
+    assert!(pgn.ends_with("\n\n12... O-O 13. O-O-O *\n"));
+
+    // movetext wraps before 80 columns, the fifth repetition ends the game
+    let mut moves = Vec::new();
+    for _ in 0..4 {
+        moves.extend(["g1f3", "g8f6", "f3g1", "f6g8"]);
+    }
+    let (_, game) = play_from(STARTING_FEN, &moves);
+    assert!(game.result.is_some());
+    let pgn = game.to_pgn(&PgnTags::default());
+    let movetext: Vec<&str> = pgn.split("\n\n").nth(1).unwrap().lines().collect();
+    assert!(movetext.len() > 1);
+    assert!(movetext.iter().all(|line| line.len() <= 80));
+    assert!(movetext[0].starts_with("1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3"));
+    assert!(pgn.ends_with(" 8.\nNg1 Ng8 1/2-1/2\n"));
+}
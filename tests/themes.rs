@@ -0,0 +1,79 @@
+use chess::theme::{parse_color, parse_themes, piece_sets, ThemeError, PIECE_FILES};
+use std::path::Path;
+
+#[test]
+fn shipped_assets() {
+    assert_eq!(piece_sets(Path::new("assets")), ["tatiana"]);
+    let text = std::fs::read_to_string("assets/themes.txt").unwrap();
+    let themes = parse_themes(&text).unwrap();
+    assert_eq!(themes[0].name, "classic");
+    assert_eq!(themes[0].light, [255, 255, 255, 255]);
+}
+
+#[test]
+fn only_complete_piece_sets_are_found() {
+    let assets = std::env::temp_dir().join(format!("chess-sets-{}", std::process::id()));
+    for set in ["full", "half"] {
+        std::fs::create_dir_all(assets.join(set)).unwrap();
+    }
+    for (n, file) in PIECE_FILES.iter().enumerate() {
+        std::fs::write(assets.join("full").join(format!("{}.png", file)), "").unwrap();
+        if n < 6 {
+            std::fs::write(assets.join("half").join(format!("{}.png", file)), "").unwrap();
+        }
+    }
+    let sets = piece_sets(&assets);
+    std::fs::remove_dir_all(&assets).unwrap();
+    assert_eq!(sets, ["full"]);
+}
+
+#[test]
+fn colors() {
+    assert_eq!(parse_color("#f0d9b5"), Some([240, 217, 181, 255]));
+    assert_eq!(parse_color("#cdd22680"), Some([205, 210, 38, 128]));
+    assert_eq!(parse_color("f0d9b5"), None);
+    assert_eq!(parse_color("#f0d9b"), None);
+    assert_eq!(parse_color("#g0d9b5"), None);
+}
+
+#[test]
+fn highlights_left_out_stay_unset() {
+    let themes =
+        parse_themes("# comment\n[brown]\nlight = #f0d9b5\ndark = #b58863\ncheck = #ff000080\n")
+            .unwrap();
+    assert_eq!(themes.len(), 1);
+    assert_eq!(themes[0].name, "brown");
+    assert_eq!(themes[0].dark, [181, 136, 99, 255]);
+    assert_eq!(themes[0].check, Some([255, 0, 0, 128]));
+    assert_eq!(themes[0].last_move, None);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(parse_themes("# nothing\n"), Err(ThemeError::NoThemes));
+    assert_eq!(
+        parse_themes("light = #ffffff\n"),
+        Err(ThemeError::OutsideTheme(1))
+    );
+    assert_eq!(
+        parse_themes("[a]\nlight = #ffffff\nborder = #000000\n"),
+        Err(ThemeError::UnknownKey {
+            line: 3,
+            key: "border".to_string()
+        })
+    );
+    assert_eq!(
+        parse_themes("[a]\nlight = white\n"),
+        Err(ThemeError::InvalidColor {
+            line: 2,
+            value: "white".to_string()
+        })
+    );
+    assert_eq!(
+        parse_themes("[a]\nlight = #ffffff\n"),
+        Err(ThemeError::MissingColor {
+            theme: "a".to_string(),
+            key: "dark"
+        })
+    );
+}
@@ -0,0 +1,123 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chess::pieces::Side;
+use chess::uci::{Go, Uci};
+
+// output the test can read back while the engine holds on to it
+#[derive(Clone, Default)]
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Shared {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Shared {
+    fn lines(&self) -> Vec<String> {
+        let text = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+        text.lines().map(|line| line.to_string()).collect()
+    }
+}
+
+fn session(commands: &[&str]) -> Vec<String> {
+    let output = Shared::default();
+    let mut uci = Uci::new(output.clone());
+    for command in commands {
+        uci.command(command);
+    }
+    uci.wait();
+    output.lines()
+}
+
+#[test]
+fn handshake() {
+    let lines = session(&["uci", "isready", "nonsense", "isready"]);
+    assert_eq!(lines[0], "id name chess");
+    assert_eq!(lines[2..], ["uciok", "readyok", "readyok"]);
+}
+
+#[test]
+fn searches_to_a_depth() {
+    let lines = session(&["position startpos moves e2e4 e7e5", "go depth 2"]);
+    assert!(lines[0].starts_with("info depth 1 score cp"));
+    assert!(lines[1].starts_with("info depth 2 "));
+    assert!(lines[2].starts_with("bestmove "));
+    assert_eq!(lines.len(), 3);
+}
+
+#[test]
+fn finds_the_mate_from_a_fen() {
+    let lines = session(&[
+        "position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1",
+        "go depth 3",
+    ]);
+    assert!(lines.iter().any(|line| line.contains("score mate 1")));
+    assert_eq!(lines.last().unwrap(), "bestmove a1a8");
+}
+
+#[test]
+fn plays_promotions_and_castling_from_moves() {
+    let lines = session(&[
+        "position fen r3k3/6P1/8/8/8/8/8/4K2R w Kq - 0 1 moves e1g1 e8c8 g7g8q",
+        "go depth 2",
+    ]);
+    // the rook castled to d8 takes the new queen
+    assert_eq!(lines.last().unwrap(), "bestmove d8g8");
+}
+
+#[test]
+fn reports_illegal_moves() {
+    let lines = session(&["position startpos moves e2e5"]);
+    assert_eq!(lines, ["info string illegal move e2e5"]);
+    let lines = session(&["position fen 8/8/8/8/8/8/8/8 w - - 0 1"]);
+    assert!(lines[0].starts_with("info string "));
+}
+
+#[test]
+fn no_move_when_the_game_is_over() {
+    let lines = session(&[
+        "position fen R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1",
+        "go depth 2",
+    ]);
+    assert_eq!(lines, ["bestmove 0000"]);
+}
+
+#[test]
+fn infinite_waits_for_stop() {
+    let output = Shared::default();
+    let mut uci = Uci::new(output.clone());
+    uci.command("position startpos");
+    uci.command("go infinite");
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!output
+        .lines()
+        .iter()
+        .any(|line| line.starts_with("bestmove")));
+    uci.command("stop");
+    assert!(output.lines().last().unwrap().starts_with("bestmove "));
+}
+
+#[test]
+fn shares_out_the_clock() {
+    let go = Go::parse(&["wtime", "60000", "btime", "30000", "winc", "1000"]);
+    assert_eq!(
+        go.limits(Side::White).movetime,
+        Some(Duration::from_millis(2750))
+    );
+    assert_eq!(
+        go.limits(Side::Black).movetime,
+        Some(Duration::from_millis(1000))
+    );
+    let go = Go::parse(&["btime", "40", "movestogo", "1"]);
+    assert_eq!(go.limits(Side::Black).movetime, Some(Duration::ZERO));
+    let go = Go::parse(&["movetime", "500", "depth", "4"]);
+    let limits = go.limits(Side::White);
+    assert_eq!(limits.movetime, Some(Duration::from_millis(500)));
+    assert_eq!(limits.depth, Some(4));
+}
@@ -0,0 +1,76 @@
+use chess::game::Game;
+use chess::grid::Grid;
+use chess::san;
+
+fn hash_after(fen: &str, moves: &[&str]) -> u64 {
+    let mut grid = Grid::new64();
+    let mut game = Game::from_fen(&mut grid, fen).unwrap();
+    for notation in moves {
+        let (from, to, promotion) = san::parse_coordinate(notation).unwrap();
+        assert!(
+            game.make_move(&mut grid, &from, &to, promotion),
+            "{}",
+            notation
+        );
+        assert_eq!(game.position.hash, game.position.key());
+    }
+    game.position.hash
+}
+
+const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[test]
+fn transpositions_share_a_key() {
+    assert_eq!(
+        hash_after(START, &["g1f3", "g8f6", "b1c3", "b8c6"]),
+        hash_after(START, &["b1c3", "b8c6", "g1f3", "g8f6"])
+    );
+    // one knight back home is not both of them back home
+    assert_ne!(
+        hash_after(START, &["g1f3", "g8f6", "f3g1"]),
+        hash_after(START, &["g1f3", "g8f6", "f3g1", "f6g8"])
+    );
+}
+
+#[test]
+fn key_matches_the_fen_of_the_same_position() {
+    let played = hash_after(START, &["e2e4", "c7c5", "g1f3"]);
+    let fen = "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2";
+    assert_eq!(played, hash_after(fen, &[]));
+}
+
+#[test]
+fn special_moves_keep_the_key_in_step() {
+    hash_after(
+        "r3k2r/6P1/8/8/5p2/8/4P3/R3K2R w KQkq - 0 1",
+        &["e2e4", "f4e3", "e1g1", "e8c8", "g7h8q", "c8b7", "h8d8"],
+    );
+}
+
+#[test]
+fn side_to_move_changes_the_key() {
+    assert_ne!(
+        hash_after("4k3/8/8/8/8/8/8/4KR2 w - - 0 1", &[]),
+        hash_after("4k3/8/8/8/8/8/8/4KR2 b - - 0 1", &[])
+    );
+}
+
+#[test]
+fn castling_rights_change_the_key() {
+    assert_ne!(
+        hash_after("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", &[]),
+        hash_after("r3k2r/8/8/8/8/8/8/R3K2R w Kkq - 0 1", &[])
+    );
+}
+
+#[test]
+fn en_passant_changes_the_key_only_with_a_pawn_to_take() {
+    assert_ne!(
+        hash_after("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", &[]),
+        hash_after("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1", &[])
+    );
+    assert_eq!(
+        hash_after("4k3/8/8/3p3P/8/8/8/4K3 w - d6 0 1", &[]),
+        hash_after("4k3/8/8/3p3P/8/8/8/4K3 w - - 0 1", &[])
+    );
+}